@@ -222,6 +222,9 @@ class CRUDBenchmark:
                 async with session.get(f"{base_url}/db/items") as response:
                     if response.status == 200:
                         items = await response.json()
+                        # Paginated servers wrap the rows in {"items": [...]}
+                        if isinstance(items, dict):
+                            items = items.get("items", [])
                         items_to_delete = [
                             item["id"] for item in items if item["id"] > 3
                         ]
@@ -0,0 +1,20 @@
+-- Baseline schema. Databases that predate migrations are brought up to it in init_db first,
+-- so every statement here must be safe to run against an existing items table.
+CREATE TABLE IF NOT EXISTS items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    description TEXT,
+    price REAL NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    version INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE INDEX IF NOT EXISTS idx_items_created_at ON items(created_at);
+CREATE INDEX IF NOT EXISTS idx_items_price ON items(price);
+
+-- Names are unique so upserts can target them. Older databases may hold duplicates,
+-- so suffix every copy after the first with its id before building the index.
+UPDATE items SET name = name || ' #' || id
+WHERE id NOT IN (SELECT MIN(id) FROM items GROUP BY name);
+DROP INDEX IF EXISTS idx_items_name;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_items_name_unique ON items(name);
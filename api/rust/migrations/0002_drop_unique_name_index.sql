@@ -0,0 +1,4 @@
+-- Names may repeat (repeated POST /db/items with one name must keep succeeding), so swap the
+-- unique index from 0001 for a plain one
+DROP INDEX IF EXISTS idx_items_name_unique;
+CREATE INDEX IF NOT EXISTS idx_items_name ON items(name);
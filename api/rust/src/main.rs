@@ -1,439 +1,5581 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    async_trait,
+    body::{Body, Bytes, HttpBody},
+    extract::{
+        multipart::{Multipart, MultipartError},
+        rejection::JsonRejection,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, DefaultBodyLimit, FromRequest, FromRequestParts, MatchedPath, Path, Query,
+        RawQuery, State,
+    },
+    http::{header, request::Parts, HeaderMap, Method, StatusCode},
     middleware::{self, Next},
-    response::{Json, Response},
-    routing::{get, post, put, delete},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse, Json, Response,
+    },
+    routing::{get, post, put},
     Router,
 };
-use serde::{Deserialize, Serialize};
-use sqlx::sqlite::SqlitePool;
+use axum_server::{accept::Accept, tls_rustls::RustlsConfig};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use futures::{
+    future::{join_all, BoxFuture},
+    stream::Stream,
+};
+use hdrhistogram::Histogram;
+use hyper_util::{
+    rt::{TokioExecutor, TokioTimer},
+    server::conn::auto,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, Exp, Normal};
+use regex::Regex;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use sqlx::{
+    sqlite::{Sqlite, SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow},
+    ConnectOptions, QueryBuilder, Row,
+};
 use std::{
-    collections::HashMap,
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    convert::Infallible,
+    fmt::Write as _,
+    net::{IpAddr, SocketAddr},
+    num::NonZeroUsize,
+    pin::Pin,
+    str::FromStr,
+    task::{Context, Poll},
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
     time::{Duration, Instant},
 };
-use tokio::time::sleep;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf},
+    net::TcpStream,
+    sync::{Notify, OwnedSemaphorePermit, Semaphore},
+    time::sleep,
+};
 use tower::ServiceBuilder;
-use tower_http::cors::CorsLayer;
+use tracing::Instrument;
+use tracing_subscriber::{filter::LevelFilter, EnvFilter};
+use tower_http::{
+    compression::CompressionLayer, cors::{AllowOrigin, CorsLayer}, limit::RequestBodyLimitLayer,
+    timeout::TimeoutLayer,
+};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use uuid::Uuid;
+use validator::{Validate, ValidationErrors};
+
+// Page served at /docs; loads Swagger UI from unpkg instead of bundling it
+const SWAGGER_UI_HTML: &str = r##"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8" />
+  <title>Axum Benchmark API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"##;
+
+// Request body cap when MAX_BODY_BYTES is unset
+const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+// Handler deadline when REQUEST_TIMEOUT_SECS is unset
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+// Pool ceiling when DB_MAX_CONNECTIONS is unset (sqlx's own default)
+const DEFAULT_DB_MAX_CONNECTIONS: u32 = 10;
+
+// How long a connection waits on a locked database when DB_BUSY_TIMEOUT_MS is unset
+const DEFAULT_DB_BUSY_TIMEOUT_MS: u64 = 5000;
+
+// Header carrying the API key for mutating requests
+const API_KEY_HEADER: &str = "x-api-key";
+// Stress workloads stay public under API_KEY; entries ending in '/' match the whole subtree
+const API_KEY_EXEMPT_ROUTES: &[&str] = &["/stress/", "/bench/run"];
+
+// Header carrying ADMIN_TOKEN for /admin routes
+const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+
+// How long browsers may cache a preflight when ALLOWED_ORIGINS is set
+const CORS_MAX_AGE_SECS: u64 = 600;
+
+// Correlation header and the longest caller-supplied value we'll reuse
+const REQUEST_ID_HEADER: &str = "x-request-id";
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+// Text format SQLite's CURRENT_TIMESTAMP writes into items.created_at
+const SQLITE_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+// Idempotency-Key handling for create_item; TTL overridable via IDEMPOTENCY_TTL_SECS
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+const DEFAULT_IDEMPOTENCY_TTL_SECS: u64 = 300;
+const IDEMPOTENCY_MAX_KEYS: usize = 100_000;
+
+// Item field length limits, in characters
+const MAX_NAME_LENGTH: u64 = 255;
+const MAX_DESCRIPTION_LENGTH: u64 = 2000;
+
+// Pagination limits for item listings
+const DEFAULT_PAGE_LIMIT: i64 = 50;
+const MAX_PAGE_LIMIT: i64 = 500;
+
+// Tail size for /db/items/recent
+const DEFAULT_RECENT_ITEMS: u32 = 10;
+const MAX_RECENT_ITEMS: u32 = 500;
+
+// Bucket count for /db/items/histogram
+const DEFAULT_HISTOGRAM_BUCKETS: u32 = 10;
+const MAX_HISTOGRAM_BUCKETS: u32 = 100;
+
+// Upper bound on ids accepted by bulk endpoints, keeping IN (...) lists bounded
+const MAX_BULK_IDS: usize = 1000;
+
+// Rows per multi-row INSERT when seeding, well under SQLite's bound-parameter limit
+const SEED_BATCH_ROWS: usize = 1000;
+
+// Largest background seed POST /db/seed/:count will start
+const MAX_SEED_JOB_ROWS: u32 = 10_000_000;
+// How long a finished seed job stays pollable
+const SEED_JOB_TTL_SECS: u64 = 3600;
+
+// Database benchmark caps
+const MAX_INSERT_BENCHMARK_ROWS: u32 = 100_000;
+const MAX_TRANSACTION_BENCHMARK_COUNT: u32 = 50_000;
+const MAX_NPLUS1_QUERIES: u32 = 10_000;
+const MAX_CONCURRENT_WRITERS: u32 = 1000;
+const MAX_BUSY_RETRIES: u32 = 10;
+
+// Write handlers' SQLITE_BUSY retries (DB_BUSY_RETRIES) and the first backoff, doubled per attempt
+const DEFAULT_DB_BUSY_RETRIES: u32 = 3;
+const BUSY_RETRY_BASE_DELAY: Duration = Duration::from_millis(5);
+
+// Shared cap for memory, disk and streaming stress sizes
+const MAX_STRESS_SIZE_MB: u64 = 100;
+
+// Chunk size for /stream/bytes; divides a MiB evenly
+const STREAM_CHUNK_BYTES: usize = 64 * 1024;
+
+// Artificial echo latency bounds
+const DEFAULT_ECHO_DELAY_MS: u64 = 1;
+const MAX_ECHO_DELAY_MS: u64 = 10_000;
+
+// /latency bounds: the largest mean accepted, and the ceiling on any drawn sleep
+const MAX_LATENCY_MEAN_MS: u64 = 10_000;
+const MAX_LATENCY_SLEEP_MS: f64 = 30_000.0;
+
+// Most elements /echo/batch accepts in one request
+const MAX_ECHO_BATCH: usize = 1000;
+
+// Total multipart bytes /upload reads; MAX_BODY_BYTES still applies first
+const MAX_UPLOAD_BYTES: u64 = 100 * 1024 * 1024;
+
+// Largest WebSocket message /ws/echo accepts
+const MAX_WS_MESSAGE_BYTES: usize = 1024 * 1024;
+
+// Round-trip cap for /stress/json
+const MAX_JSON_STRESS_ITERATIONS: u64 = 1_000_000;
+
+// Search-loop cap for /stress/regex
+const MAX_REGEX_STRESS_ITERATIONS: u64 = 1_000_000;
+
+// Longest memory_stress may keep its allocation alive
+const MAX_MEMORY_HOLD_MS: u64 = 60_000;
+
+// Client count above which idle rate-limit buckets are pruned
+const RATE_LIMIT_MAX_TRACKED_CLIENTS: usize = 10_000;
+
+// Requests kept by /stats/slowest when SLOW_SAMPLE_SIZE is unset
+const DEFAULT_SLOW_SAMPLE_SIZE: usize = 20;
+
+// Slowest request /stats can tell apart (microseconds); saturating_record clamps anything above it
+const MAX_TRACKED_LATENCY_MICROS: u64 = 3_600_000_000;
+
+// How long /ready waits for a pooled connection before reporting not ready
+const READY_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Upper bounds (seconds) of the request latency histogram buckets
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0,
+];
+
+// Most tasks concurrency_stress may spawn, and the work each one does
+const MAX_CONCURRENCY_TASKS: u32 = 10_000;
+const CONCURRENCY_TASK_ITERATIONS: u64 = 10_000;
+
+// cpu_stress loop iterations between cooperative yields
+const CPU_YIELD_INTERVAL: u64 = 1_000_000;
+
+// cpu_stress iteration cap when MAX_CPU_ITERATIONS is unset
+const DEFAULT_MAX_CPU_ITERATIONS: u64 = 1_000_000_000;
+
+// Recursive Fibonacci is exponential, so its input is capped separately
+const MAX_FIB_N: u64 = 40;
 
 // Application state
 #[derive(Clone)]
 pub struct AppState {
     pub db: SqlitePool,
+    pub metrics: Arc<Metrics>,
+    pub stats: Arc<Mutex<StatsState>>,
+    pub slow_requests: Arc<SlowRequests>,
+    pub start_time: Instant,
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    pub api_key: Option<Arc<str>>,
+    pub idempotency: Arc<IdempotencyStore>,
+    pub list_cache: Option<Arc<ListCache>>,
+    pub admin_token: Option<Arc<str>>,
+    pub shutdown: Arc<Notify>,
+    pub seed_jobs: Arc<SeedJobs>,
+    pub busy_retries: u32,
+    pub max_cpu_iterations: u64,
+}
+
+impl AppState {
+    // Call after every committed write to the items table
+    fn invalidate_list_cache(&self) {
+        if let Some(cache) = &self.list_cache {
+            cache.invalidate();
+        }
+    }
+}
+
+// Per-client token buckets; each IP may burst up to `rps` requests and refills at `rps`/s
+#[derive(Debug)]
+pub struct RateLimiter {
+    rps: f64,
+    buckets: Mutex<HashMap<IpAddr, TokenBucket>>,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(rps: u32) -> Self {
+        Self {
+            rps: f64::from(rps),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Takes a token for `ip`, or returns how many seconds until one is available
+    fn check(&self, ip: IpAddr) -> Result<(), u64> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        // Forget idle clients so the map can't grow without bound
+        if buckets.len() > RATE_LIMIT_MAX_TRACKED_CLIENTS {
+            buckets.retain(|_, bucket| now.duration_since(bucket.updated) < Duration::from_secs(60));
+        }
+
+        let bucket = buckets.entry(ip).or_insert(TokenBucket {
+            tokens: self.rps,
+            updated: now,
+        });
+        let refill = now.duration_since(bucket.updated).as_secs_f64() * self.rps;
+        bucket.tokens = (bucket.tokens + refill).min(self.rps);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) / self.rps).ceil().max(1.0) as u64)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IdempotencyEntry {
+    InFlight,
+    Completed(i64),
+}
+
+// What claiming a key found: a fresh reservation, a request still running, or the item it made
+#[derive(Debug)]
+enum IdempotencyClaim<'a> {
+    Reserved(IdempotencyReservation<'a>),
+    InFlight,
+    Completed(i64),
+}
+
+// Idempotency-Key -> created item id, remembered for `ttl`
+#[derive(Debug)]
+pub struct IdempotencyStore {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (IdempotencyEntry, Instant)>>,
+}
+
+impl IdempotencyStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Checks and reserves under one lock, so two requests with the same key can't both insert
+    fn claim(&self, key: &str) -> IdempotencyClaim<'_> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some(&(IdempotencyEntry::InFlight, _)) => return IdempotencyClaim::InFlight,
+            Some(&(IdempotencyEntry::Completed(item_id), stored)) if stored.elapsed() < self.ttl => {
+                return IdempotencyClaim::Completed(item_id)
+            }
+            _ => {}
+        }
+
+        // Drop expired keys so the map can't grow without bound
+        if entries.len() >= IDEMPOTENCY_MAX_KEYS {
+            let ttl = self.ttl;
+            entries.retain(|_, (entry, stored)| {
+                *entry == IdempotencyEntry::InFlight || stored.elapsed() < ttl
+            });
+        }
+        entries.insert(key.to_string(), (IdempotencyEntry::InFlight, Instant::now()));
+        IdempotencyClaim::Reserved(IdempotencyReservation {
+            store: self,
+            key: Some(key.to_string()),
+        })
+    }
+
+    // Forgets a completed key whose item has since been deleted, unless it was already replaced
+    fn forget(&self, key: &str, item_id: i64) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.get(key).map(|(entry, _)| *entry) == Some(IdempotencyEntry::Completed(item_id)) {
+            entries.remove(key);
+        }
+    }
+}
+
+// An in-flight key; released on drop unless completed, so a failed or cancelled create can be retried
+#[derive(Debug)]
+struct IdempotencyReservation<'a> {
+    store: &'a IdempotencyStore,
+    key: Option<String>,
+}
+
+impl IdempotencyReservation<'_> {
+    fn complete(mut self, item_id: i64) {
+        if let Some(key) = self.key.take() {
+            let mut entries = self.store.entries.lock().unwrap();
+            entries.insert(key, (IdempotencyEntry::Completed(item_id), Instant::now()));
+        }
+    }
+}
+
+impl Drop for IdempotencyReservation<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.store.entries.lock().unwrap().remove(&key);
+        }
+    }
+}
+
+// Seed job id -> progress; finished jobs are dropped once they're SEED_JOB_TTL_SECS old
+#[derive(Debug, Default)]
+pub struct SeedJobs {
+    jobs: Mutex<HashMap<Uuid, (SeedProgress, Option<Instant>)>>,
+}
+
+impl SeedJobs {
+    fn start(&self, total: u32) -> Uuid {
+        let mut jobs = self.jobs.lock().unwrap();
+
+        // Prune on every start so polled-out jobs can't accumulate
+        let ttl = Duration::from_secs(SEED_JOB_TTL_SECS);
+        jobs.retain(|_, (_, finished)| finished.is_none_or(|at| at.elapsed() < ttl));
+
+        let job_id = Uuid::new_v4();
+        let progress = SeedProgress {
+            inserted: 0,
+            total,
+            done: false,
+            error: None,
+        };
+        jobs.insert(job_id, (progress, None));
+        job_id
+    }
+
+    fn record_batch(&self, job_id: Uuid, rows: u32) {
+        if let Some((progress, _)) = self.jobs.lock().unwrap().get_mut(&job_id) {
+            progress.inserted += rows;
+        }
+    }
+
+    fn finish(&self, job_id: Uuid, error: Option<String>) {
+        if let Some((progress, finished)) = self.jobs.lock().unwrap().get_mut(&job_id) {
+            progress.done = true;
+            progress.error = error;
+            *finished = Some(Instant::now());
+        }
+    }
+
+    fn get(&self, job_id: Uuid) -> Option<SeedProgress> {
+        let ttl = Duration::from_secs(SEED_JOB_TTL_SECS);
+        match self.jobs.lock().unwrap().get(&job_id) {
+            Some((_, Some(finished))) if finished.elapsed() >= ttl => None,
+            Some((progress, _)) => Some(progress.clone()),
+            None => None,
+        }
+    }
+}
+
+// Single-slot cache of the last GET /db/items page, keyed by its raw query string.
+// The generation counter stops a read that raced a write from caching stale rows.
+#[derive(Debug)]
+pub struct ListCache {
+    ttl: Duration,
+    generation: AtomicU64,
+    entry: RwLock<Option<(Instant, String, Arc<PaginatedItems>)>>,
+}
+
+impl ListCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            generation: AtomicU64::new(0),
+            entry: RwLock::new(None),
+        }
+    }
+
+    fn get(&self, query: &str) -> Option<Arc<PaginatedItems>> {
+        match &*self.entry.read().unwrap() {
+            Some((cached_at, cached_query, page))
+                if cached_query == query && cached_at.elapsed() < self.ttl =>
+            {
+                Some(page.clone())
+            }
+            _ => None,
+        }
+    }
+
+    // Read before querying, then pass to `insert`
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    fn insert(&self, generation: u64, query: String, page: Arc<PaginatedItems>) {
+        let mut entry = self.entry.write().unwrap();
+        if self.generation.load(Ordering::Acquire) == generation {
+            *entry = Some((Instant::now(), query, page));
+        }
+    }
+
+    fn invalidate(&self) {
+        let mut entry = self.entry.write().unwrap();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        *entry = None;
+    }
+}
+
+// Latency samples (microseconds), overall and per matched route, and body byte totals behind /stats
+#[derive(Debug)]
+pub struct StatsState {
+    latency: Histogram<u64>,
+    routes: HashMap<String, Histogram<u64>>,
+    payload_bytes: HashMap<String, PayloadBytes>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct PayloadBytes {
+    bytes_in: u64,
+    bytes_out: u64,
+}
+
+impl Default for StatsState {
+    fn default() -> Self {
+        Self {
+            latency: latency_histogram(),
+            routes: HashMap::new(),
+            payload_bytes: HashMap::new(),
+        }
+    }
+}
+
+// Explicit bounds matter: saturating_record never auto-resizes, so an unbounded
+// Histogram::new would clamp every sample to its tiny initial range
+fn latency_histogram() -> Histogram<u64> {
+    Histogram::new_with_bounds(1, MAX_TRACKED_LATENCY_MICROS, 3).expect("valid histogram bounds")
+}
+
+impl StatsState {
+    fn record(&mut self, route: &str, elapsed: Duration, bytes_in: u64, bytes_out: u64) {
+        let micros = elapsed.as_micros() as u64;
+        self.latency.saturating_record(micros);
+        self.routes
+            .entry(route.to_string())
+            .or_insert_with(latency_histogram)
+            .saturating_record(micros);
+        let payload = self.payload_bytes.entry(route.to_string()).or_default();
+        payload.bytes_in += bytes_in;
+        payload.bytes_out += bytes_out;
+    }
+}
+
+// The `capacity` slowest requests seen, behind /stats/slowest. Once full, `threshold` holds the
+// fastest kept latency, so most requests are turned away by one atomic load without locking.
+#[derive(Debug)]
+pub struct SlowRequests {
+    capacity: usize,
+    threshold: AtomicU64,
+    samples: Mutex<Vec<SlowRequest>>,
+}
+
+impl SlowRequests {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            threshold: AtomicU64::new(if capacity == 0 { u64::MAX } else { 0 }),
+            samples: Mutex::new(Vec::with_capacity(capacity)),
+        }
+    }
+
+    fn is_candidate(&self, elapsed: Duration) -> bool {
+        elapsed.as_micros() as u64 > self.threshold.load(Ordering::Relaxed)
+    }
+
+    // Replaces the fastest kept sample when full; callers check is_candidate first
+    fn record(&self, sample: SlowRequest) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() < self.capacity {
+            samples.push(sample);
+        } else {
+            let Some((fastest, kept)) = samples
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, kept)| kept.latency_micros)
+            else {
+                return;
+            };
+            if sample.latency_micros <= kept.latency_micros {
+                return;
+            }
+            samples[fastest] = sample;
+        }
+        if samples.len() == self.capacity {
+            let fastest = samples.iter().map(|kept| kept.latency_micros).min();
+            self.threshold.store(fastest.unwrap_or(u64::MAX), Ordering::Relaxed);
+        }
+    }
+
+    // Slowest first
+    fn snapshot(&self) -> Vec<SlowRequest> {
+        let mut samples = self.samples.lock().unwrap().clone();
+        samples.sort_by_key(|sample| std::cmp::Reverse(sample.latency_micros));
+        samples
+    }
+}
+
+// Prometheus counters, recorded by the timing middleware
+#[derive(Debug, Default)]
+pub struct Metrics {
+    requests: Mutex<HashMap<(String, String, u16), u64>>,
+    in_flight: AtomicI64,
+    duration_buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    duration_count: AtomicU64,
+    duration_sum_micros: AtomicU64,
+}
+
+impl Metrics {
+    fn observe(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        *self
+            .requests
+            .lock()
+            .unwrap()
+            .entry((method.to_string(), route.to_string(), status))
+            .or_insert(0) += 1;
+
+        let seconds = elapsed.as_secs_f64();
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|&le| seconds <= le) {
+            self.duration_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        }
+        self.duration_count.fetch_add(1, Ordering::Relaxed);
+        self.duration_sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    // Prometheus text exposition format
+    fn render(&self) -> String {
+        let mut out = String::new();
+
+        let mut requests: Vec<_> = self
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(key, count)| (key.clone(), *count))
+            .collect();
+        requests.sort();
+        out.push_str("# HELP http_requests_total Total number of HTTP requests.\n");
+        out.push_str("# TYPE http_requests_total counter\n");
+        for ((method, route, status), count) in requests {
+            let _ = writeln!(
+                out,
+                "http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                method, route, status, count
+            );
+        }
+
+        out.push_str("# HELP http_requests_in_flight Number of HTTP requests currently being served.\n");
+        out.push_str("# TYPE http_requests_in_flight gauge\n");
+        let _ = writeln!(
+            out,
+            "http_requests_in_flight {}",
+            self.in_flight.load(Ordering::Relaxed)
+        );
+
+        out.push_str("# HELP http_request_duration_seconds HTTP request latency.\n");
+        out.push_str("# TYPE http_request_duration_seconds histogram\n");
+        let mut cumulative = 0;
+        for (le, bucket) in LATENCY_BUCKETS.iter().zip(&self.duration_buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(
+                out,
+                "http_request_duration_seconds_bucket{{le=\"{}\"}} {}",
+                le, cumulative
+            );
+        }
+        let count = self.duration_count.load(Ordering::Relaxed);
+        let _ = writeln!(out, "http_request_duration_seconds_bucket{{le=\"+Inf\"}} {}", count);
+        let _ = writeln!(
+            out,
+            "http_request_duration_seconds_sum {}",
+            self.duration_sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0
+        );
+        let _ = writeln!(out, "http_request_duration_seconds_count {}", count);
+
+        out
+    }
 }
 
 // Data models
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 pub struct Item {
+    #[validate(length(min = 1, max = MAX_NAME_LENGTH))]
     pub name: String,
+    #[validate(length(max = MAX_DESCRIPTION_LENGTH))]
     pub description: Option<String>,
+    #[validate(range(min = 0.0))]
     pub price: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+// `description` is doubly optional so an explicit null clears it while omission leaves it unchanged
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct ItemPatch {
+    #[validate(length(min = 1, max = MAX_NAME_LENGTH))]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    #[validate(length(max = MAX_DESCRIPTION_LENGTH))]
+    pub description: Option<Option<String>>,
+    #[validate(range(min = 0.0))]
+    pub price: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct ItemResponse {
     pub id: i64,
     pub name: String,
     pub description: Option<String>,
     pub price: f64,
     pub created_at: String,
+    // Bumped on every update; send it back in If-Match for optimistic concurrency
+    pub version: i64,
+    // Only filled when the request asks for ?currency=
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_display: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct EchoRequest {
-    pub message: String,
-    pub data: Option<serde_json::Value>,
+// The CSV export's contracted columns; kept apart from ItemResponse so new fields don't leak in
+#[derive(Debug, Serialize, sqlx::FromRow)]
+struct CsvItemRow {
+    id: i64,
+    name: String,
+    description: Option<String>,
+    price: f64,
+    created_at: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct EchoResponse {
-    pub message: String,
-    pub data: Option<serde_json::Value>,
-    pub timestamp: String,
-    pub processing_time_ms: f64,
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CreateItemQuery {
+    // Validate only: nothing is inserted and the response has id 0
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct HealthResponse {
-    pub status: String,
-    pub timestamp: String,
-    pub database: String,
+// The would-be item from a dry-run create_item
+#[derive(Debug, Serialize)]
+pub struct DryRunItem {
+    #[serde(flatten)]
+    pub item: ItemResponse,
+    pub dry_run: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CpuStressResponse {
-    pub iterations: u64,
-    pub result: u64,
-    pub processing_time_ms: f64,
-    pub timestamp: String,
+// Body of PUT /db/items/by-name/:name; the name comes from the path
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ItemUpsert {
+    pub description: Option<String>,
+    pub price: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct MemoryStressResponse {
-    pub allocated_bytes: usize,
-    pub allocated_mb: u64,
-    pub processing_time_ms: f64,
-    pub timestamp: String,
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpsertResponse {
+    #[serde(flatten)]
+    pub item: ItemResponse,
+    pub created: bool,
 }
 
-// Database initialization with performance optimizations
-pub async fn init_db() -> Result<SqlitePool, sqlx::Error> {
-    let pool = SqlitePool::connect_with(
-        sqlx::sqlite::SqliteConnectOptions::new()
-            .filename("benchmark.db")
-            .create_if_missing(true)
-            .pragma("journal_mode", "WAL")
-            .pragma("synchronous", "NORMAL")
-            .pragma("cache_size", "64000")
-            .pragma("temp_store", "memory")
-            .pragma("mmap_size", "268435456")
-            .pragma("foreign_keys", "off")
-            .pragma("auto_vacuum", "none")
-            .pragma("page_size", "4096")
-    ).await?;
-
-    // Create table
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS items (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL,
-            description TEXT,
-            price REAL NOT NULL,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )
-        "#,
-    )
-    .execute(&pool)
-    .await?;
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PaginatedItems {
+    pub items: Vec<ItemResponse>,
+    pub limit: i64,
+    pub offset: i64,
+    pub total: i64,
+}
 
-    // Create indexes
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_items_created_at ON items(created_at)")
-        .execute(&pool).await?;
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_items_name ON items(name)")
-        .execute(&pool).await?;
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_items_price ON items(price)")
-        .execute(&pool).await?;
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct KeysetPage {
+    pub items: Vec<ItemResponse>,
+    pub limit: i64,
+    // Pass back as after_id; null once there are no more items
+    pub next_cursor: Option<i64>,
+}
 
-    sqlx::query("PRAGMA optimize").execute(&pool).await?;
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct KeysetPageQuery {
+    pub after_id: Option<i64>,
+    pub limit: Option<i64>,
+}
 
-    // Insert sample data if empty
-    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
-        .fetch_one(&pool).await?;
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListItemsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub sort_by: Option<String>,
+    pub order: Option<String>,
+}
 
-    if count == 0 {
-        let sample_items = vec![
-            ("Laptop", Some("High-performance laptop"), 999.99),
-            ("Mouse", Some("Wireless mouse"), 29.99),
-            ("Keyboard", Some("Mechanical keyboard"), 79.99),
-        ];
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Currency {
+    Usd,
+    Eur,
+    Gbp,
+    Jpy,
+}
 
-        for (name, description, price) in sample_items {
-            sqlx::query("INSERT INTO items (name, description, price) VALUES (?, ?, ?)")
-                .bind(name)
-                .bind(description)
-                .bind(price)
-                .execute(&pool)
-                .await?;
+impl Currency {
+    // Symbol, minor-unit digits and thousands grouping, e.g. "$1,234.50" or "¥1,235"
+    fn format(self, amount: f64) -> String {
+        let (symbol, decimals) = match self {
+            Currency::Usd => ("$", 2),
+            Currency::Eur => ("€", 2),
+            Currency::Gbp => ("£", 2),
+            Currency::Jpy => ("¥", 0),
+        };
+        let fixed = format!("{:.*}", decimals, amount.abs());
+        let (whole, fraction) = fixed.split_at(fixed.find('.').unwrap_or(fixed.len()));
+
+        let mut display = String::with_capacity(fixed.len() + whole.len() / 3 + 2);
+        if amount.is_sign_negative() && amount != 0.0 {
+            display.push('-');
+        }
+        display.push_str(symbol);
+        for (i, digit) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i).is_multiple_of(3) {
+                display.push(',');
+            }
+            display.push(digit);
         }
+        display.push_str(fraction);
+        display
     }
+}
 
-    Ok(pool)
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CurrencyQuery {
+    #[param(inline)]
+    pub currency: Option<Currency>,
 }
 
-// Middleware
-pub async fn add_process_time_header(
-    request: axum::extract::Request,
-    next: Next,
-) -> Response {
-    let start = Instant::now();
-    let mut response = next.run(request).await;
-    let elapsed = start.elapsed();
-    
-    response.headers_mut().insert(
-        "x-process-time",
-        elapsed.as_secs_f64().to_string().parse().unwrap(),
-    );
-    
-    response
+impl CurrencyQuery {
+    fn apply(&self, item: &mut ItemResponse) {
+        if let Some(currency) = self.currency {
+            item.price_display = Some(currency.format(item.price));
+        }
+    }
 }
 
-// Utility functions
-fn current_iso_timestamp() -> String {
-    chrono::Utc::now().to_rfc3339()
+// Keys ?fields= may name; price_display is derived from price, the rest are columns
+const ITEM_FIELDS: [&str; 7] = [
+    "id",
+    "name",
+    "description",
+    "price",
+    "created_at",
+    "version",
+    "price_display",
+];
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FieldsQuery {
+    // Comma-separated subset of the item fields, e.g. `id,name`; omitted means all of them
+    pub fields: Option<String>,
 }
 
-// Route handlers
-pub async fn read_root() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "Hello": "World",
-        "timestamp": current_iso_timestamp()
-    }))
+impl FieldsQuery {
+    fn parse(&self) -> Result<Option<Vec<&'static str>>, ApiError> {
+        let Some(fields) = &self.fields else {
+            return Ok(None);
+        };
+        let mut selected = Vec::new();
+        for field in fields.split(',').map(str::trim) {
+            let known = ITEM_FIELDS
+                .iter()
+                .find(|&&known| known == field)
+                .ok_or_else(|| ApiError::bad_request(format!("unknown field {:?}", field)))?;
+            if !selected.contains(known) {
+                selected.push(*known);
+            }
+        }
+        Ok(Some(selected))
+    }
 }
 
-pub async fn read_item(
-    Path(item_id): Path<u32>,
-    Query(params): Query<HashMap<String, String>>,
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ItemFilter {
+    // Exact match, served by idx_items_name; see /db/items/search for substrings
+    pub name: Option<String>,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    #[param(value_type = Option<String>)]
+    pub created_after: Option<NaiveDateTime>,
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    #[param(value_type = Option<String>)]
+    pub created_before: Option<NaiveDateTime>,
+}
+
+impl ItemFilter {
+    // Rejects empty ranges such as min_price > max_price
+    fn is_consistent(&self) -> bool {
+        let prices_ok = match (self.min_price, self.max_price) {
+            (Some(min_price), Some(max_price)) => min_price <= max_price,
+            _ => true,
+        };
+        let dates_ok = match (self.created_after, self.created_before) {
+            (Some(after), Some(before)) => after <= before,
+            _ => true,
+        };
+        prices_ok && dates_ok
+    }
+}
+
+// Body of PATCH /db/items/bulk
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkPriceUpdate {
+    pub filter: BulkPriceFilter,
+    pub set: BulkPriceSet,
+}
+
+// At least one bound is required, so a bulk update can never cover the whole table
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkPriceFilter {
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkPriceSet {
+    pub price_multiplier: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkUpdateResponse {
+    pub rows_affected: u64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SeedJobStarted {
+    #[schema(value_type = String)]
+    pub job_id: Uuid,
+    pub total: u32,
+}
+
+// Finished jobs stay pollable for an hour; error is set if a batch failed and the job stopped early
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SeedProgress {
+    pub inserted: u32,
+    pub total: u32,
+    pub done: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IdList {
+    pub ids: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BatchGetQuery {
+    // Emit null for ids that don't exist instead of dropping them
+    #[serde(default)]
+    pub include_missing: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ConfirmQuery {
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EchoRequest {
+    pub message: String,
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SelectBenchmarkQuery {
+    // Sleep after the query, bounded like the echo delay
+    #[serde(default)]
+    pub extra_delay_ms: u64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EchoQuery {
+    #[serde(default = "default_echo_delay_ms")]
+    pub delay_ms: u64,
+}
+
+fn default_echo_delay_ms() -> u64 {
+    DEFAULT_ECHO_DELAY_MS
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FailQuery {
+    // Replays an earlier roll; a fresh seed is drawn when omitted
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FailResponse {
+    pub rate: u8,
+    pub roll: f64,
+    pub failed: bool,
+    pub seed: u64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LatencyDist {
+    // Standard deviation of a quarter of the mean, clamped at zero
+    #[default]
+    Normal,
+    Exponential,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LatencyQuery {
+    #[serde(default)]
+    #[param(inline)]
+    pub dist: LatencyDist,
+    // Replays an earlier draw; a fresh seed is drawn when omitted
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LatencyResponse {
+    pub dist: LatencyDist,
+    pub mean_ms: u64,
+    pub sampled_ms: f64,
+    pub slept_ms: f64,
+    pub capped: bool,
+    pub seed: u64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WsEchoQuery {
+    #[serde(default)]
+    pub timestamp: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UploadedField {
+    pub name: Option<String>,
+    pub file_name: Option<String>,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UploadResponse {
+    pub fields: Vec<UploadedField>,
+    pub total_bytes: u64,
+    pub processing_time_ms: f64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EchoResponse {
+    pub message: String,
+    pub data: Option<serde_json::Value>,
+    pub timestamp: String,
+    pub processing_time_ms: f64,
+    pub client: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HealthResponse {
+    pub status: String,
+    pub timestamp: String,
+    pub database: String,
+    pub uptime_seconds: u64,
+    pub version: String,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CpuWorkload {
+    #[default]
+    Mulacc,
+    Fib,
+    Prime,
+    Sqrt,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CpuStressQuery {
+    #[serde(default)]
+    pub workload: CpuWorkload,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CpuStressResponse {
+    pub workload: CpuWorkload,
+    pub iterations: u64,
+    pub result: u64,
+    pub processing_time_ms: f64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MemoryStressQuery {
+    #[serde(default)]
+    pub hold_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MemoryStressResponse {
+    pub allocated_bytes: usize,
+    pub allocated_mb: u64,
+    pub hold_ms: u64,
+    pub processing_time_ms: f64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BenchRunRequest {
+    pub cpu_iterations: u64,
+    pub memory_mb: u64,
+    pub db_select_count: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SelectBenchmarkResult {
+    pub rows_fetched: usize,
+    pub processing_time_ms: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BenchRunResponse {
+    pub cpu: CpuStressResponse,
+    pub memory: MemoryStressResponse,
+    pub db_select: SelectBenchmarkResult,
+    pub total_time_ms: f64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VersionResponse {
+    pub version: String,
+    pub git_sha: String,
+    pub build_timestamp: String,
+    pub rustc_version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ItemExistsResponse {
+    pub exists: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ItemCountResponse {
+    pub count: i64,
+    pub timestamp: String,
+}
+
+// min/avg/max are null for an empty table; sum is 0
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct PriceStatsResponse {
+    pub count: i64,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    pub avg_price: Option<f64>,
+    pub sum_price: f64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistogramQuery {
+    pub buckets: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HistogramBucket {
+    pub range_low: f64,
+    pub range_high: f64,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PragmaResponse {
+    pub journal_mode: String,
+    pub synchronous: Option<i64>,
+    pub cache_size: Option<i64>,
+    pub mmap_size: Option<i64>,
+    pub page_size: Option<i64>,
+    pub temp_store: Option<i64>,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PoolStatsResponse {
+    pub size: u32,
+    pub idle: usize,
+    pub max: u32,
+    pub min: u32,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AnalyzeResponse {
+    pub processing_time_ms: f64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CheckpointResponse {
+    pub busy: i64,
+    pub log_frames: i64,
+    pub checkpointed_frames: i64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MemoryUsageResponse {
+    pub rss_bytes: u64,
+    pub virtual_bytes: u64,
+    pub allocated_bytes: u64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CpuUsageResponse {
+    pub user_seconds: Option<f64>,
+    pub system_seconds: Option<f64>,
+    pub logical_cores: usize,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DbPingResponse {
+    pub roundtrip_ms: f64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExplainQuery {
+    pub sql: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct QueryPlanRow {
+    pub id: i64,
+    pub parent: i64,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct JsonStressResponse {
+    pub iterations: u64,
+    pub payload_bytes: usize,
+    pub processing_time_ms: f64,
+    pub parses_per_second: f64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RegexStressRequest {
+    pub pattern: String,
+    pub haystack: String,
+    pub iterations: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RegexStressResponse {
+    pub iterations: u64,
+    pub matches_per_iteration: u64,
+    pub total_matches: u64,
+    pub compile_ms: f64,
+    pub processing_time_ms: f64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DiskStressResponse {
+    pub bytes_written: u64,
+    pub write_ms: f64,
+    pub read_ms: f64,
+    pub processing_time_ms: f64,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StatsResponse {
+    pub request_count: u64,
+    pub avg_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub routes: HashMap<String, u64>,
+    pub bytes_in_total: u64,
+    pub bytes_out_total: u64,
+    pub route_payloads: HashMap<String, RoutePayloadStats>,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SlowRequest {
+    pub method: String,
+    pub path: String,
+    pub latency_ms: f64,
+    pub timestamp: String,
+    #[serde(skip)]
+    latency_micros: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RouteLatency {
+    pub route: String,
+    pub count: u64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+// Streamed bodies without a known length count as zero bytes
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RoutePayloadStats {
+    pub avg_bytes_in: f64,
+    pub avg_bytes_out: f64,
+}
+
+// Set by propagate_request_id for handlers and extractors
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+tokio::task_local! {
+    // Request id of the request being served, so ApiError can include it without extractors
+    static REQUEST_ID: String;
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+// Error type rendered as { "error", "code", "timestamp", "request_id" }, plus "errors" for validation failures
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+    pub errors: Vec<FieldError>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn validation(errors: Vec<FieldError>) -> Self {
+        Self {
+            errors,
+            ..Self::bad_request("validation failed")
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        eprintln!("Database error: {:?}", e);
+        Self::internal("database error")
+    }
+}
+
+// Field order is sorted so the body is stable despite validator's HashMap
+impl From<ValidationErrors> for ApiError {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut fields: Vec<FieldError> = errors
+            .field_errors()
+            .into_iter()
+            .flat_map(|(field, errors)| {
+                errors.iter().map(move |error| FieldError {
+                    field,
+                    message: describe_validation_error(error),
+                })
+            })
+            .collect();
+        fields.sort_by(|a, b| a.field.cmp(b.field).then_with(|| a.message.cmp(&b.message)));
+        Self::validation(fields)
+    }
+}
+
+fn describe_validation_error(error: &validator::ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+    match (error.code.as_ref(), error.params.get("min"), error.params.get("max")) {
+        ("length", Some(min), Some(max)) => {
+            format!("must be between {} and {} characters", min, max)
+        }
+        ("length", None, Some(max)) => format!("must be at most {} characters", max),
+        ("range", Some(min), None) => format!("must be >= {}", min),
+        (code, _, _) => format!("failed {} check", code),
+    }
+}
+
+// SQLITE_BUSY and its extended variants (BUSY_RECOVERY, BUSY_SNAPSHOT, BUSY_TIMEOUT) share primary code 5
+fn is_busy(e: &sqlx::Error) -> bool {
+    e.as_database_error()
+        .and_then(|db_error| db_error.code())
+        .and_then(|code| code.parse::<i32>().ok())
+        .is_some_and(|code| code & 0xff == 5)
+}
+
+// Reruns `query` while it fails with SQLITE_BUSY, up to `retries` extra attempts
+async fn retry_busy<T, F, Fut>(retries: u32, mut query: F) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, sqlx::Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match query().await {
+            Err(e) if is_busy(&e) && attempt < retries => {
+                sleep(BUSY_RETRY_BASE_DELAY * 2u32.pow(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let mut body = serde_json::json!({
+            "error": self.message,
+            "code": self.status.as_u16(),
+            "timestamp": current_iso_timestamp()
+        });
+        if !self.errors.is_empty() {
+            body["errors"] = serde_json::json!(self.errors);
+        }
+        if let Ok(request_id) = REQUEST_ID.try_with(Clone::clone) {
+            body["request_id"] = serde_json::json!(request_id);
+        }
+        (self.status, Json(body)).into_response()
+    }
+}
+
+// Negotiated response encoding, picked from the Accept header; ?pretty=true indents JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Json,
+    PrettyJson,
+    MsgPack,
+}
+
+#[derive(Debug, Deserialize)]
+struct PrettyQuery {
+    #[serde(default)]
+    pretty: bool,
+}
+
+fn is_msgpack(value: Option<&header::HeaderValue>) -> bool {
+    value
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value.contains("application/msgpack") || value.contains("application/x-msgpack")
+        })
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ResponseFormat {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if is_msgpack(parts.headers.get(header::ACCEPT)) {
+            return Ok(Self::MsgPack);
+        }
+        // Other extractors read the same query string, so a malformed value just means compact
+        let pretty = Query::<PrettyQuery>::try_from_uri(&parts.uri)
+            .is_ok_and(|Query(query)| query.pretty);
+        Ok(if pretty { Self::PrettyJson } else { Self::Json })
+    }
+}
+
+impl ResponseFormat {
+    pub fn render<T: Serialize>(self, value: &T) -> Response {
+        match self {
+            Self::Json => Json(value).into_response(),
+            Self::PrettyJson => match serde_json::to_vec_pretty(value) {
+                Ok(bytes) => ([(header::CONTENT_TYPE, "application/json")], bytes).into_response(),
+                Err(e) => ApiError::internal(format!("JSON encoding failed: {}", e)).into_response(),
+            },
+            Self::MsgPack => match rmp_serde::to_vec_named(value) {
+                Ok(bytes) => {
+                    ([(header::CONTENT_TYPE, "application/msgpack")], bytes).into_response()
+                }
+                Err(e) => ApiError::internal(format!("MessagePack encoding failed: {}", e))
+                    .into_response(),
+            },
+        }
+    }
+}
+
+// Json extractor whose rejections render as ApiError, pointing at the offending line and column
+#[derive(FromRequest)]
+#[from_request(via(Json), rejection(ApiError))]
+pub struct ApiJson<T>(pub T);
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        let status = rejection.status();
+        // axum wraps the serde_json error in serde_path_to_error, a few sources down
+        let parse_error = std::iter::successors(
+            Some(&rejection as &(dyn std::error::Error + 'static)),
+            |e| e.source(),
+        )
+        .find_map(|e| e.downcast_ref::<serde_path_to_error::Error<serde_json::Error>>());
+        let Some(parse_error) = parse_error else {
+            return Self::new(status, rejection.body_text());
+        };
+
+        let inner = parse_error.inner();
+        let position = format!(" at line {} column {}", inner.line(), inner.column());
+        let detail = inner.to_string();
+        let detail = detail.strip_suffix(&position).unwrap_or(&detail);
+        let kind = if inner.is_data() { "invalid request body" } else { "invalid JSON" };
+        // "." is the document root and "?" an unknown position (e.g. a truncated body)
+        let path = parse_error.path().to_string();
+        let location = if path == "." || path == "?" {
+            String::new()
+        } else {
+            format!(" (field `{}`)", path)
+        };
+
+        Self::new(
+            status,
+            format!(
+                "{} at line {}, column {}{}: {}",
+                kind,
+                inner.line(),
+                inner.column(),
+                location,
+                detail
+            ),
+        )
+    }
+}
+
+// Request body decoded from MessagePack when Content-Type says so, JSON otherwise
+pub struct Payload<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Payload<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(
+        request: axum::extract::Request,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        if is_msgpack(request.headers().get(header::CONTENT_TYPE)) {
+            let bytes = Bytes::from_request(request, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            rmp_serde::from_slice(&bytes).map(Payload).map_err(|e| {
+                ApiError::bad_request(format!("invalid MessagePack body: {}", e)).into_response()
+            })
+        } else {
+            let ApiJson(value) = ApiJson::<T>::from_request(request, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            Ok(Payload(value))
+        }
+    }
+}
+
+// Weak ETag over every column, so any PUT/PATCH that changes the row changes the tag
+fn item_etag(item: &ItemResponse) -> String {
+    let mut hasher = DefaultHasher::new();
+    item.id.hash(&mut hasher);
+    item.name.hash(&mut hasher);
+    item.description.hash(&mut hasher);
+    item.price.to_bits().hash(&mut hasher);
+    item.created_at.hash(&mut hasher);
+    item.version.hash(&mut hasher);
+    item.price_display.hash(&mut hasher);
+    format!("W/\"{:016x}\"", hasher.finish())
+}
+
+// SELECT list for a sparse field set
+fn sparse_columns(fields: &[&str]) -> String {
+    let mut columns: Vec<&str> = fields
+        .iter()
+        .map(|&field| if field == "price_display" { "price" } else { field })
+        .collect();
+    columns.sort_unstable();
+    columns.dedup();
+    columns.join(", ")
+}
+
+// Only the requested keys; price_display stays absent without ?currency=
+fn sparse_item(
+    row: &SqliteRow,
+    fields: &[&str],
+    currency: &CurrencyQuery,
+) -> Result<serde_json::Value, sqlx::Error> {
+    let mut item = serde_json::Map::new();
+    for &field in fields {
+        let value = match field {
+            "id" | "version" => serde_json::json!(row.try_get::<i64, _>(field)?),
+            "name" | "created_at" => serde_json::json!(row.try_get::<String, _>(field)?),
+            "description" => serde_json::json!(row.try_get::<Option<String>, _>(field)?),
+            "price" => serde_json::json!(row.try_get::<f64, _>(field)?),
+            _ => match currency.currency {
+                Some(currency) => serde_json::json!(currency.format(row.try_get("price")?)),
+                None => continue,
+            },
+        };
+        item.insert(field.to_string(), value);
+    }
+    Ok(serde_json::Value::Object(item))
+}
+
+// If-Match carries an item version such as `3` or `"3"`; absent or `*` means unconditional
+fn if_match_version(headers: &HeaderMap) -> Result<Option<i64>, ApiError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let value = value
+        .to_str()
+        .map_err(|_| ApiError::bad_request("If-Match must be an item version"))?
+        .trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    value
+        .trim_matches('"')
+        .parse()
+        .map(Some)
+        .map_err(|_| ApiError::bad_request("If-Match must be an item version"))
+}
+
+// Compares the current version with If-Match before a guarded UPDATE
+async fn check_item_version(
+    db: &SqlitePool,
+    item_id: i64,
+    expected: Option<i64>,
+) -> Result<(), ApiError> {
+    let current: i64 = sqlx::query_scalar("SELECT version FROM items WHERE id = ?")
+        .bind(item_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("item not found"))?;
+    match expected {
+        Some(expected) if expected != current => Err(version_conflict(current)),
+        _ => Ok(()),
+    }
+}
+
+// A guarded UPDATE matched nothing: the item was deleted or its version moved on
+async fn stale_write(db: &SqlitePool, item_id: i64) -> ApiError {
+    let current = sqlx::query_scalar("SELECT version FROM items WHERE id = ?")
+        .bind(item_id)
+        .fetch_optional(db)
+        .await;
+    match current {
+        Ok(Some(current)) => version_conflict(current),
+        Ok(None) => ApiError::not_found("item not found"),
+        Err(e) => e.into(),
+    }
+}
+
+fn version_conflict(current: i64) -> ApiError {
+    ApiError::new(
+        StatusCode::CONFLICT,
+        format!("item was modified; current version is {}", current),
+    )
+}
+
+// Weak comparison: "W/" prefixes are ignored and "*" matches anything
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let Some(value) = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+    else {
+        return false;
+    };
+    let opaque = etag.trim_start_matches("W/");
+    value
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == opaque)
+}
+
+// Database initialization with performance optimizations
+// DATABASE_PATH selects the file (default benchmark.db); ":memory:" keeps everything in RAM
+pub async fn init_db(
+    max_connections: u32,
+    min_connections: u32,
+    seed_count: Option<u32>,
+    slow_query_threshold: Option<Duration>,
+    busy_timeout: Duration,
+) -> Result<SqlitePool, sqlx::Error> {
+    let database_path =
+        std::env::var("DATABASE_PATH").unwrap_or_else(|_| "benchmark.db".to_string());
+    let in_memory = database_path == ":memory:";
+
+    let mut options = if in_memory {
+        // Shared-cache URL so every pooled connection sees the same database
+        SqliteConnectOptions::from_str("sqlite::memory:")?
+    } else {
+        SqliteConnectOptions::new()
+            .filename(&database_path)
+            .create_if_missing(true)
+            .pragma("journal_mode", "WAL")
+    };
+    options = options
+        .pragma("synchronous", "NORMAL")
+        .pragma("cache_size", "64000")
+        .pragma("temp_store", "memory")
+        .pragma("mmap_size", "268435456")
+        .pragma("foreign_keys", "off")
+        .pragma("auto_vacuum", "none")
+        .pragma("page_size", "4096")
+        // Set by sqlx on every connection it opens, so the whole pool waits rather than failing
+        .busy_timeout(busy_timeout);
+    // sqlx warns about statements over 1s by default; only log slow ones when asked to
+    options = match slow_query_threshold {
+        Some(threshold) => options.log_slow_statements(log::LevelFilter::Warn, threshold),
+        None => options.log_slow_statements(log::LevelFilter::Off, Duration::MAX),
+    };
+
+    let mut pool_options = SqlitePoolOptions::new()
+        .max_connections(max_connections)
+        .min_connections(min_connections);
+    if in_memory {
+        // The in-memory database disappears with its last connection, so never reap them
+        pool_options = pool_options
+            .min_connections(min_connections.max(1))
+            .idle_timeout(None)
+            .max_lifetime(None);
+    }
+    let pool = pool_options.connect_with(options).await?;
+
+    // Databases created before migrations (and before optimistic concurrency) lack the version
+    // column, which the baseline migration can't add with CREATE TABLE IF NOT EXISTS
+    let needs_version: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'items') \
+         AND NOT EXISTS(SELECT 1 FROM pragma_table_info('items') WHERE name = 'version')",
+    )
+    .fetch_one(&pool)
+    .await?;
+    if needs_version {
+        sqlx::query("ALTER TABLE items ADD COLUMN version INTEGER NOT NULL DEFAULT 1")
+            .execute(&pool)
+            .await?;
+    }
+
+    // Schema lives in migrations/; applied versions are tracked in _sqlx_migrations
+    sqlx::migrate!().run(&pool).await?;
+
+    sqlx::query("PRAGMA optimize").execute(&pool).await?;
+
+    // Insert sample data if empty
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&pool).await?;
+
+    if let (0, Some(seed_count)) = (count, seed_count) {
+        seed_generated_items(&pool, seed_count).await?;
+    } else if count == 0 {
+        let sample_items = vec![
+            ("Laptop", Some("High-performance laptop"), 999.99),
+            ("Mouse", Some("Wireless mouse"), 29.99),
+            ("Keyboard", Some("Mechanical keyboard"), 79.99),
+        ];
+
+        for (name, description, price) in sample_items {
+            sqlx::query("INSERT INTO items (name, description, price) VALUES (?, ?, ?)")
+                .bind(name)
+                .bind(description)
+                .bind(price)
+                .execute(&pool)
+                .await?;
+        }
+    }
+
+    Ok(pool)
+}
+
+// Seeds `count` rows named item_{i} with random prices, in multi-row INSERTs inside one transaction
+async fn seed_generated_items(pool: &SqlitePool, count: u32) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let ids: Vec<u32> = (1..=count).collect();
+    for chunk in ids.chunks(SEED_BATCH_ROWS) {
+        seed_batch_insert("item", chunk).build().execute(&mut *tx).await?;
+    }
+    tx.commit().await?;
+
+    println!("🌱 Seeded {} items", count);
+    Ok(())
+}
+
+// One multi-row INSERT of seeded items named {prefix}_{i}
+fn seed_batch_insert(prefix: &str, ids: &[u32]) -> QueryBuilder<'static, Sqlite> {
+    let mut insert = QueryBuilder::<Sqlite>::new("INSERT INTO items (name, description, price) ");
+    insert.push_values(ids, |mut row, i| {
+        row.push_bind(format!("{}_{}", prefix, i))
+            .push_bind("seeded item")
+            .push_bind(random_price());
+    });
+    insert
+}
+
+// Middleware
+// Reuses the caller's x-request-id or mints a UUID, then exposes it to extensions, spans,
+// error bodies and the response header
+pub async fn propagate_request_id(mut request: axum::extract::Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= MAX_REQUEST_ID_LENGTH)
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    request.extensions_mut().insert(RequestId(request_id.clone()));
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), next.run(request).instrument(span))
+        .await;
+    if let Ok(value) = header::HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+// One structured event per request at debug, so the default info filter keeps the hot path quiet;
+// runs inside the request span so it carries the request id
+pub async fn access_log(request: axum::extract::Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    tracing::debug!(
+        method = %method,
+        path = %path,
+        status = response.status().as_u16(),
+        latency_ms = start.elapsed().as_secs_f64() * 1000.0,
+        "request completed"
+    );
+
+    response
+}
+
+// Gives axum's bodyless 405s the standard error body; the method router sets Allow on the way out
+pub async fn describe_method_not_allowed(request: axum::extract::Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let response = next.run(request).await;
+    if response.status() != StatusCode::METHOD_NOT_ALLOWED {
+        return response;
+    }
+    ApiError::new(
+        StatusCode::METHOD_NOT_ALLOWED,
+        format!("method {} not allowed on this route", method),
+    )
+    .into_response()
+}
+
+// Enabled by GLOBAL_DELAY_MS; simulates a slow dependency in front of every handler
+pub async fn inject_delay(
+    State(delay): State<Duration>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Response {
+    if !delay.is_zero() {
+        sleep(delay).await;
+    }
+    next.run(request).await
+}
+
+pub async fn add_process_time_header(
+    State(state): State<AppState>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let bytes_in = body_bytes(request.headers(), request.body());
+    // Uri clones share their buffer; the path is only copied out for slow requests
+    let uri = request.uri().clone();
+
+    state.metrics.in_flight.fetch_add(1, Ordering::Relaxed);
+    let start = Instant::now();
+    let mut response = next.run(request).await;
+    let elapsed = start.elapsed();
+    state.metrics.in_flight.fetch_sub(1, Ordering::Relaxed);
+    state
+        .metrics
+        .observe(&method, &route, response.status().as_u16(), elapsed);
+    let bytes_out = body_bytes(response.headers(), response.body());
+    state
+        .stats
+        .lock()
+        .unwrap()
+        .record(&route, elapsed, bytes_in, bytes_out);
+    if state.slow_requests.is_candidate(elapsed) {
+        state.slow_requests.record(SlowRequest {
+            method,
+            path: uri.path().to_string(),
+            latency_ms: elapsed.as_secs_f64() * 1000.0,
+            timestamp: current_iso_timestamp(),
+            latency_micros: elapsed.as_micros() as u64,
+        });
+    }
+    
+    response.headers_mut().insert(
+        "x-process-time",
+        elapsed.as_secs_f64().to_string().parse().unwrap(),
+    );
+    
+    response
+}
+
+// Content-Length when declared, else the body's exact size if it knows it, else 0
+fn body_bytes(headers: &HeaderMap, body: &Body) -> u64 {
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .or_else(|| body.size_hint().exact())
+        .unwrap_or(0)
+}
+
+// Enabled by RATE_LIMIT_RPS; answers 429 with Retry-After once a client's bucket is empty
+pub async fn rate_limit(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Response {
+    if let Some(limiter) = &state.rate_limiter {
+        if let Err(retry_after) = limiter.check(addr.ip()) {
+            return (
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                ApiError::new(StatusCode::TOO_MANY_REQUESTS, "rate limit exceeded"),
+            )
+                .into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
+// Enabled by API_KEY; mutating methods need a matching x-api-key header (API_KEY_EXEMPT_ROUTES aside)
+pub async fn require_api_key(
+    State(state): State<AppState>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Response {
+    let Some(api_key) = &state.api_key else {
+        return next.run(request).await;
+    };
+
+    let is_write = matches!(
+        *request.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+    let path = request.uri().path();
+    let exempt = API_KEY_EXEMPT_ROUTES.iter().any(|route| {
+        if route.ends_with('/') {
+            path.starts_with(route)
+        } else {
+            path == *route
+        }
+    });
+    if is_write && !exempt {
+        let provided = request
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok());
+        if provided != Some(&**api_key) {
+            return ApiError::new(StatusCode::UNAUTHORIZED, "missing or invalid API key")
+                .into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
+// Utility functions
+fn current_iso_timestamp() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+// Parses an environment variable, falling back to `default` when it is unset
+fn env_parse<T>(key: &str, default: T) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match std::env::var(key) {
+        Ok(value) => value
+            .parse()
+            .map_err(|e| format!("invalid {} {:?}: {}", key, value, e)),
+        Err(_) => Ok(default),
+    }
+}
+
+// Random price in [0, 1000) rounded to cents
+fn random_price() -> f64 {
+    (rand::random::<f64>() * 100_000.0).round() / 100.0
+}
+
+// Wraps any present value (including null) in Some, leaving absent fields to `default`
+fn deserialize_present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+// Accepts RFC 3339 (converted to UTC), a naive "YYYY-MM-DDTHH:MM:SS" or a bare date
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<Option<NaiveDateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(value) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(&value) {
+        return Ok(Some(timestamp.naive_utc()));
+    }
+    if let Ok(timestamp) = NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Ok(Some(timestamp));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0));
+    }
+    Err(serde::de::Error::custom(format!(
+        "invalid timestamp {:?}: expected ISO-8601",
+        value
+    )))
+}
+
+// Column and direction are interpolated into SQL, so only allow-listed values pass
+fn item_sort_clause(sort_by: Option<&str>, order: Option<&str>) -> Option<String> {
+    let column = match sort_by.unwrap_or("id") {
+        "id" => "id",
+        "name" => "name",
+        "price" => "price",
+        "created_at" => "created_at",
+        _ => return None,
+    };
+    let direction = match order.unwrap_or("asc").to_ascii_lowercase().as_str() {
+        "asc" => "ASC",
+        "desc" => "DESC",
+        _ => return None,
+    };
+    Some(format!("{} {}", column, direction))
+}
+
+// Appends the WHERE clause for any filters that were supplied
+fn push_item_filter(qb: &mut QueryBuilder<'_, Sqlite>, filter: &ItemFilter) {
+    let mut separator = " WHERE ";
+    if let Some(name) = &filter.name {
+        qb.push(separator).push("name = ").push_bind(name.clone());
+        separator = " AND ";
+    }
+    if let Some(min_price) = filter.min_price {
+        qb.push(separator).push("price >= ").push_bind(min_price);
+        separator = " AND ";
+    }
+    if let Some(max_price) = filter.max_price {
+        qb.push(separator).push("price <= ").push_bind(max_price);
+        separator = " AND ";
+    }
+    // created_at is stored as SQLite's "YYYY-MM-DD HH:MM:SS" UTC text, so compare in that form
+    if let Some(created_after) = filter.created_after {
+        qb.push(separator)
+            .push("created_at >= ")
+            .push_bind(created_after.format(SQLITE_TIMESTAMP_FORMAT).to_string());
+        separator = " AND ";
+    }
+    if let Some(created_before) = filter.created_before {
+        qb.push(separator)
+            .push("created_at <= ")
+            .push_bind(created_before.format(SQLITE_TIMESTAMP_FORMAT).to_string());
+    }
+}
+
+// Route handlers
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "general",
+    responses(
+        (status = 200, description = "Hello world payload"),
+    ),
+)]
+pub async fn read_root() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "Hello": "World",
+        "timestamp": current_iso_timestamp()
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/items/{item_id}",
+    tag = "general",
+    params(
+        ("item_id" = u32, Path, description = "Item id"),
+        ("q" = Option<String>, Query, description = "Optional query string echoed back"),
+    ),
+    responses(
+        (status = 200, description = "Item id and query echoed back"),
+    ),
+)]
+pub async fn read_item(
+    Path(item_id): Path<u32>,
+    Query(params): Query<HashMap<String, String>>,
 ) -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "item_id": item_id,
         "q": params.get("q"),
         "timestamp": current_iso_timestamp()
-    }))
+    }))
+}
+
+// Liveness: answers without touching the database so a slow pool can't fail it
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "observability",
+    responses(
+        (status = 200, description = "Liveness status", body = HealthResponse),
+    ),
+)]
+pub async fn health_check(State(state): State<AppState>) -> Json<HealthResponse> {
+    let db_status = if state.db.is_closed() {
+        "disconnected"
+    } else {
+        "connected"
+    };
+
+    Json(HealthResponse {
+        status: "healthy".to_string(),
+        timestamp: current_iso_timestamp(),
+        database: db_status.to_string(),
+        uptime_seconds: state.start_time.elapsed().as_secs(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/version",
+    tag = "observability",
+    responses(
+        (status = 200, description = "Build metadata captured by build.rs", body = VersionResponse),
+    ),
+)]
+pub async fn version() -> Json<VersionResponse> {
+    Json(VersionResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_sha: env!("BUILD_GIT_SHA").to_string(),
+        build_timestamp: env!("BUILD_TIMESTAMP").to_string(),
+        rustc_version: env!("BUILD_RUSTC_VERSION").to_string(),
+    })
+}
+
+// Readiness: a connection must be acquired and queried within READY_TIMEOUT
+#[utoipa::path(
+    get,
+    path = "/ready",
+    tag = "observability",
+    responses(
+        (status = 200, description = "Database reachable"),
+        (status = 503, description = "Database unreachable"),
+    ),
+)]
+pub async fn readiness_check(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let probe = async {
+        let mut conn = state.db.acquire().await?;
+        sqlx::query("SELECT 1").execute(&mut *conn).await
+    };
+
+    let (status, ready) = match tokio::time::timeout(READY_TIMEOUT, probe).await {
+        Ok(Ok(_)) => (StatusCode::OK, true),
+        _ => (StatusCode::SERVICE_UNAVAILABLE, false),
+    };
+
+    (
+        status,
+        Json(serde_json::json!({
+            "status": if ready { "ready" } else { "not ready" },
+            "timestamp": current_iso_timestamp()
+        })),
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/echo",
+    tag = "echo",
+    params(
+        EchoQuery,
+    ),
+    request_body = EchoRequest,
+    responses(
+        (status = 200, description = "Echoed payload", body = EchoResponse),
+        (status = 400, description = "Delay out of range"),
+    ),
+)]
+pub async fn echo_post(
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    Query(params): Query<EchoQuery>,
+    ApiJson(payload): ApiJson<EchoRequest>,
+) -> Result<Json<EchoResponse>, StatusCode> {
+    if params.delay_ms > MAX_ECHO_DELAY_MS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    Ok(Json(echo_one(payload, params.delay_ms, client).await))
+}
+
+// Echoes each element in turn, so delay_ms applies per element like separate requests would
+#[utoipa::path(
+    post,
+    path = "/echo/batch",
+    tag = "echo",
+    params(
+        EchoQuery,
+    ),
+    request_body = Vec<EchoRequest>,
+    responses(
+        (status = 200, description = "Echoed payloads, in request order", body = Vec<EchoResponse>),
+        (status = 400, description = "Delay out of range or batch too large"),
+    ),
+)]
+pub async fn echo_batch(
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    Query(params): Query<EchoQuery>,
+    ApiJson(payloads): ApiJson<Vec<EchoRequest>>,
+) -> Result<Json<Vec<EchoResponse>>, StatusCode> {
+    if params.delay_ms > MAX_ECHO_DELAY_MS || payloads.len() > MAX_ECHO_BATCH {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut responses = Vec::with_capacity(payloads.len());
+    for payload in payloads {
+        responses.push(echo_one(payload, params.delay_ms, client).await);
+    }
+
+    Ok(Json(responses))
+}
+
+async fn echo_one(payload: EchoRequest, delay_ms: u64, client: SocketAddr) -> EchoResponse {
+    let start = Instant::now();
+    sleep(Duration::from_millis(delay_ms)).await;
+    let processing_time = start.elapsed().as_secs_f64() * 1000.0;
+
+    EchoResponse {
+        message: payload.message,
+        data: payload.data,
+        timestamp: current_iso_timestamp(),
+        processing_time_ms: processing_time,
+        client: client.to_string(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/echo/{message}",
+    tag = "echo",
+    params(
+        ("message" = String, Path, description = "Message to echo"),
+        EchoQuery,
+    ),
+    responses(
+        (status = 200, description = "Echoed message"),
+        (status = 400, description = "Delay out of range"),
+    ),
+)]
+pub async fn echo_get(
+    Path(message): Path<String>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    Query(params): Query<EchoQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if params.delay_ms > MAX_ECHO_DELAY_MS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let start = Instant::now();
+    sleep(Duration::from_millis(params.delay_ms)).await;
+    let processing_time = start.elapsed().as_secs_f64() * 1000.0;
+    
+    Ok(Json(serde_json::json!({
+        "message": message,
+        "timestamp": current_iso_timestamp(),
+        "processing_time_ms": processing_time,
+        "client": client.to_string()
+    })))
+}
+
+// Counts each part chunk by chunk and discards it, so only parsing is measured
+#[utoipa::path(
+    post,
+    path = "/upload",
+    tag = "echo",
+    request_body(content = String, content_type = "multipart/form-data", description = "Any fields or files"),
+    responses(
+        (status = 200, description = "Per-field sizes", body = UploadResponse),
+        (status = 400, description = "Malformed multipart body"),
+        (status = 413, description = "Upload exceeds the size cap"),
+    ),
+)]
+pub async fn upload(mut multipart: Multipart) -> Result<Json<UploadResponse>, ApiError> {
+    let multipart_error = |e: MultipartError| ApiError::new(e.status(), e.body_text());
+
+    let start = Instant::now();
+    let mut fields = Vec::new();
+    let mut total_bytes = 0u64;
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        let name = field.name().map(str::to_string);
+        let file_name = field.file_name().map(str::to_string);
+        let mut bytes = 0u64;
+        while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+            bytes += chunk.len() as u64;
+            total_bytes += chunk.len() as u64;
+            if total_bytes > MAX_UPLOAD_BYTES {
+                return Err(ApiError::new(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    format!("upload exceeds {} bytes", MAX_UPLOAD_BYTES),
+                ));
+            }
+        }
+        fields.push(UploadedField {
+            name,
+            file_name,
+            bytes,
+        });
+    }
+
+    Ok(Json(UploadResponse {
+        fields,
+        total_bytes,
+        processing_time_ms: start.elapsed().as_secs_f64() * 1000.0,
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "observability",
+    responses(
+        (status = 200, description = "Prometheus text exposition", content_type = "text/plain"),
+    ),
+)]
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/stats",
+    tag = "observability",
+    responses(
+        (status = 200, description = "Rolling latency statistics", body = StatsResponse),
+    ),
+)]
+pub async fn stats(State(state): State<AppState>) -> Json<StatsResponse> {
+    let stats = state.stats.lock().unwrap();
+    let latency = &stats.latency;
+    let to_ms = |micros: u64| micros as f64 / 1000.0;
+
+    let route_payloads = stats
+        .payload_bytes
+        .iter()
+        .map(|(route, payload)| {
+            // record() bumps both maps together, so every route here has a count
+            let requests = stats.routes[route].len() as f64;
+            (
+                route.clone(),
+                RoutePayloadStats {
+                    avg_bytes_in: payload.bytes_in as f64 / requests,
+                    avg_bytes_out: payload.bytes_out as f64 / requests,
+                },
+            )
+        })
+        .collect();
+
+    Json(StatsResponse {
+        request_count: latency.len(),
+        avg_ms: latency.mean() / 1000.0,
+        min_ms: to_ms(latency.min()),
+        max_ms: to_ms(latency.max()),
+        p50_ms: to_ms(latency.value_at_quantile(0.50)),
+        p95_ms: to_ms(latency.value_at_quantile(0.95)),
+        p99_ms: to_ms(latency.value_at_quantile(0.99)),
+        routes: stats
+            .routes
+            .iter()
+            .map(|(route, latency)| (route.clone(), latency.len()))
+            .collect(),
+        bytes_in_total: stats.payload_bytes.values().map(|p| p.bytes_in).sum(),
+        bytes_out_total: stats.payload_bytes.values().map(|p| p.bytes_out).sum(),
+        route_payloads,
+        timestamp: current_iso_timestamp(),
+    })
+}
+
+// Keyed by matched path, so /db/items/:item_id is one entry however many ids are hit
+#[utoipa::path(
+    get,
+    path = "/stats/routes",
+    tag = "observability",
+    responses(
+        (status = 200, description = "Latency percentiles per route, sorted by route", body = Vec<RouteLatency>),
+    ),
+)]
+pub async fn route_stats(State(state): State<AppState>) -> Json<Vec<RouteLatency>> {
+    let stats = state.stats.lock().unwrap();
+    let to_ms = |micros: u64| micros as f64 / 1000.0;
+
+    let mut routes: Vec<RouteLatency> = stats
+        .routes
+        .iter()
+        .map(|(route, latency)| RouteLatency {
+            route: route.clone(),
+            count: latency.len(),
+            p50_ms: to_ms(latency.value_at_quantile(0.50)),
+            p95_ms: to_ms(latency.value_at_quantile(0.95)),
+            p99_ms: to_ms(latency.value_at_quantile(0.99)),
+            max_ms: to_ms(latency.max()),
+        })
+        .collect();
+    routes.sort_by(|a, b| a.route.cmp(&b.route));
+    Json(routes)
+}
+
+#[utoipa::path(
+    get,
+    path = "/stats/slowest",
+    tag = "observability",
+    responses(
+        (status = 200, description = "The SLOW_SAMPLE_SIZE slowest requests, slowest first", body = Vec<SlowRequest>),
+    ),
+)]
+pub async fn slowest_requests(State(state): State<AppState>) -> Json<Vec<SlowRequest>> {
+    Json(state.slow_requests.snapshot())
+}
+
+// Pages from /proc/self/statm; allocated_bytes is the data+stack segment, the closest proxy for heap use
+#[utoipa::path(
+    get,
+    path = "/sys/memory",
+    tag = "observability",
+    responses(
+        (status = 200, description = "Process memory usage", body = MemoryUsageResponse),
+        (status = 501, description = "Not supported on this platform"),
+    ),
+)]
+pub async fn sys_memory() -> Result<Json<MemoryUsageResponse>, ApiError> {
+    #[cfg(target_os = "linux")]
+    {
+        let statm = tokio::fs::read_to_string("/proc/self/statm")
+            .await
+            .map_err(|e| ApiError::internal(format!("could not read /proc/self/statm: {}", e)))?;
+        let pages: Vec<u64> = statm
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map_err(|e| ApiError::internal(format!("malformed /proc/self/statm: {}", e)))?;
+        let [size, resident, _shared, _text, _lib, data, ..] = pages[..] else {
+            return Err(ApiError::internal("malformed /proc/self/statm: too few fields"));
+        };
+        // SAFETY: sysconf has no preconditions
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as u64;
+
+        Ok(Json(MemoryUsageResponse {
+            rss_bytes: resident * page_size,
+            virtual_bytes: size * page_size,
+            allocated_bytes: data * page_size,
+            timestamp: current_iso_timestamp(),
+        }))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err(ApiError::new(
+            StatusCode::NOT_IMPLEMENTED,
+            "memory usage is only available on Linux",
+        ))
+    }
+}
+
+// CPU times come from getrusage(RUSAGE_SELF) and are null on non-Unix platforms
+#[utoipa::path(
+    get,
+    path = "/sys/cpu",
+    tag = "observability",
+    responses(
+        (status = 200, description = "Process CPU time", body = CpuUsageResponse),
+    ),
+)]
+pub async fn sys_cpu() -> Json<CpuUsageResponse> {
+    let logical_cores = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+
+    #[cfg(unix)]
+    let (user_seconds, system_seconds) = {
+        let timeval_seconds =
+            |tv: libc::timeval| tv.tv_sec as f64 + tv.tv_usec as f64 / 1_000_000.0;
+        // SAFETY: rusage is plain old data and getrusage fully initializes it on success
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } == 0 {
+            (
+                Some(timeval_seconds(usage.ru_utime)),
+                Some(timeval_seconds(usage.ru_stime)),
+            )
+        } else {
+            (None, None)
+        }
+    };
+    #[cfg(not(unix))]
+    let (user_seconds, system_seconds) = (None, None);
+
+    Json(CpuUsageResponse {
+        user_seconds,
+        system_seconds,
+        logical_cores,
+        timestamp: current_iso_timestamp(),
+    })
+}
+
+// Streams `size_mb` MiB of 64 KiB chunks lazily, so memory stays flat and the client sets the pace
+#[utoipa::path(
+    get,
+    path = "/stream/bytes/{size_mb}",
+    tag = "stress",
+    params(
+        ("size_mb" = u64, Path, description = "Megabytes to stream"),
+    ),
+    responses(
+        (status = 200, description = "Chunked byte stream", content_type = "application/octet-stream"),
+        (status = 400, description = "Size out of range"),
+    ),
+)]
+pub async fn stream_bytes(Path(size_mb): Path<u64>) -> Result<Response, StatusCode> {
+    if size_mb > MAX_STRESS_SIZE_MB {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let total_bytes = size_mb * 1024 * 1024;
+    let chunk = Bytes::from(vec![b'x'; STREAM_CHUNK_BYTES]);
+    let chunks = total_bytes / STREAM_CHUNK_BYTES as u64;
+    let stream = tokio_stream::iter((0..chunks).map(move |_| Ok::<_, Infallible>(chunk.clone())));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (header::CONTENT_LENGTH, total_bytes.to_string()),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+// Pushes the /stats request count and mean latency once per second until the client goes away
+#[utoipa::path(
+    get,
+    path = "/stream/stats",
+    tag = "observability",
+    responses(
+        (status = 200, description = "Server-sent stream of latency statistics", content_type = "text/event-stream"),
+    ),
+)]
+pub async fn stream_stats(
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let interval = tokio::time::interval(Duration::from_secs(1));
+    let stream = futures::stream::unfold((interval, state), |(mut interval, state)| async move {
+        interval.tick().await;
+        let snapshot = {
+            let stats = state.stats.lock().unwrap();
+            serde_json::json!({
+                "request_count": stats.latency.len(),
+                "avg_ms": stats.latency.mean() / 1000.0,
+                "timestamp": current_iso_timestamp()
+            })
+        };
+        let event = Event::default().event("stats").data(snapshot.to_string());
+        Some((Ok(event), (interval, state)))
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+// Echoes every frame back; `?timestamp=true` prefixes text frames with the server time
+#[utoipa::path(
+    get,
+    path = "/ws/echo",
+    tag = "echo",
+    params(
+        WsEchoQuery,
+    ),
+    responses(
+        (status = 101, description = "WebSocket upgrade"),
+    ),
+)]
+pub async fn ws_echo(ws: WebSocketUpgrade, Query(params): Query<WsEchoQuery>) -> Response {
+    ws.max_message_size(MAX_WS_MESSAGE_BYTES)
+        .on_upgrade(move |socket| handle_ws_echo(socket, params.timestamp))
+}
+
+async fn handle_ws_echo(mut socket: WebSocket, timestamp: bool) {
+    while let Some(Ok(message)) = socket.recv().await {
+        let reply = match message {
+            Message::Text(text) if timestamp => {
+                Message::Text(format!("{} {}", current_iso_timestamp(), text))
+            }
+            Message::Text(_) | Message::Binary(_) => message,
+            // Pings are answered by the protocol layer, and the recv after a close
+            // flushes the close reply and then ends the stream
+            Message::Close(_) | Message::Ping(_) | Message::Pong(_) => continue,
+        };
+        if socket.send(reply).await.is_err() {
+            break;
+        }
+    }
+}
+
+// Database CRUD operations - NO COMPILE-TIME MACROS
+#[utoipa::path(
+    get,
+    path = "/db/items",
+    tag = "items",
+    params(
+        ListItemsQuery,
+        ItemFilter,
+        CurrencyQuery,
+        FieldsQuery,
+        ("pretty" = Option<bool>, Query, description = "Indent the JSON response"),
+    ),
+    responses(
+        (status = 200, description = "Page of items", body = PaginatedItems),
+        (status = 400, description = "Invalid paging, sort, filter or field parameters"),
+    ),
+)]
+pub async fn get_all_items(
+    State(state): State<AppState>,
+    Query(params): Query<ListItemsQuery>,
+    Query(filter): Query<ItemFilter>,
+    Query(currency): Query<CurrencyQuery>,
+    Query(fields): Query<FieldsQuery>,
+    RawQuery(raw_query): RawQuery,
+    format: ResponseFormat,
+) -> Result<Response, StatusCode> {
+    // Rendered directly so the unknown-field message survives this handler's StatusCode errors
+    let fields = match fields.parse() {
+        Ok(fields) => fields,
+        Err(e) => return Ok(e.into_response()),
+    };
+    let raw_query = raw_query.unwrap_or_default();
+    // Only full pages are cached
+    let cache = state.list_cache.as_deref().filter(|_| fields.is_none());
+    if let Some(page) = cache.and_then(|cache| cache.get(&raw_query)) {
+        return Ok(with_cache_status(format.render(&*page), "HIT"));
+    }
+    let generation = cache.map(ListCache::generation);
+
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(0, MAX_PAGE_LIMIT);
+    let offset = params.offset.unwrap_or(0);
+    if offset < 0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let order_by = item_sort_clause(params.sort_by.as_deref(), params.order.as_deref())
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    if !filter.is_consistent() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut count_query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM items");
+    push_item_filter(&mut count_query, &filter);
+    let total: i64 = count_query
+        .build_query_scalar()
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| {
+            eprintln!("Database error in get_all_items: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let columns = match &fields {
+        Some(fields) => sparse_columns(fields),
+        None => "id, name, description, price, created_at, version".to_string(),
+    };
+    let mut items_query = QueryBuilder::<Sqlite>::new(format!("SELECT {} FROM items", columns));
+    push_item_filter(&mut items_query, &filter);
+    items_query
+        .push(format!(" ORDER BY {} LIMIT ", order_by))
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+
+    if let Some(fields) = fields {
+        let items = items_query
+            .build()
+            .fetch_all(&state.db)
+            .await
+            .and_then(|rows| {
+                rows.iter()
+                    .map(|row| sparse_item(row, &fields, &currency))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|e| {
+                eprintln!("Database error in get_all_items: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        return Ok(format.render(&serde_json::json!({
+            "items": items,
+            "limit": limit,
+            "offset": offset,
+            "total": total
+        })));
+    }
+
+    let mut items: Vec<ItemResponse> = items_query
+        .build_query_as()
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| {
+            eprintln!("Database error in get_all_items: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    items.iter_mut().for_each(|item| currency.apply(item));
+
+    let page = PaginatedItems {
+        items,
+        limit,
+        offset,
+        total,
+    };
+    match (cache, generation) {
+        (Some(cache), Some(generation)) => {
+            let page = Arc::new(page);
+            let response = with_cache_status(format.render(&*page), "MISS");
+            cache.insert(generation, raw_query, page);
+            Ok(response)
+        }
+        _ => Ok(format.render(&page)),
+    }
+}
+
+fn with_cache_status(mut response: Response, status: &'static str) -> Response {
+    response
+        .headers_mut()
+        .insert("x-cache", header::HeaderValue::from_static(status));
+    response
+}
+
+// Keyset counterpart to the OFFSET listing: seeks on the primary key, so deep pages stay cheap
+#[utoipa::path(
+    get,
+    path = "/db/items/page",
+    tag = "items",
+    params(
+        KeysetPageQuery,
+    ),
+    responses(
+        (status = 200, description = "Items after the cursor, by id", body = KeysetPage),
+    ),
+)]
+pub async fn get_items_page(
+    State(state): State<AppState>,
+    Query(params): Query<KeysetPageQuery>,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(0, MAX_PAGE_LIMIT);
+
+    let items: Vec<ItemResponse> = sqlx::query_as(
+        "SELECT id, name, description, price, created_at, version FROM items \
+         WHERE id > ? ORDER BY id LIMIT ?",
+    )
+    .bind(params.after_id.unwrap_or(0))
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await?;
+
+    // A short page means the end was reached
+    let next_cursor = if items.len() as i64 == limit {
+        items.last().map(|item| item.id)
+    } else {
+        None
+    };
+
+    Ok(format.render(&KeysetPage {
+        items,
+        limit,
+        next_cursor,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/db/items/count",
+    tag = "items",
+    params(
+        ItemFilter,
+    ),
+    responses(
+        (status = 200, description = "Number of matching items", body = ItemCountResponse),
+        (status = 400, description = "Invalid filter"),
+    ),
+)]
+pub async fn count_items(
+    State(state): State<AppState>,
+    Query(filter): Query<ItemFilter>,
+) -> Result<Json<ItemCountResponse>, StatusCode> {
+    if !filter.is_consistent() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM items");
+    push_item_filter(&mut query, &filter);
+    let count: i64 = query
+        .build_query_scalar()
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| {
+            eprintln!("Database error in count_items: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(ItemCountResponse {
+        count,
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/db/items/stats",
+    tag = "items",
+    responses(
+        (status = 200, description = "Price aggregates over all items", body = PriceStatsResponse),
+    ),
+)]
+pub async fn item_price_stats(
+    State(state): State<AppState>,
+) -> Result<Json<PriceStatsResponse>, ApiError> {
+    // TOTAL() is SUM() that yields 0.0 instead of NULL on no rows
+    let stats: PriceStatsResponse = sqlx::query_as(
+        "SELECT COUNT(*) AS count, MIN(price) AS min_price, MAX(price) AS max_price, \
+         AVG(price) AS avg_price, TOTAL(price) AS sum_price FROM items",
+    )
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(Json(stats))
+}
+
+// Equal-width buckets between the min and max price, counted in SQL; the max lands in the last bucket
+#[utoipa::path(
+    get,
+    path = "/db/items/histogram",
+    tag = "items",
+    params(
+        HistogramQuery,
+    ),
+    responses(
+        (status = 200, description = "Price buckets, empty when there are no items", body = Vec<HistogramBucket>),
+        (status = 400, description = "Bucket count out of range"),
+    ),
+)]
+pub async fn item_price_histogram(
+    State(state): State<AppState>,
+    Query(params): Query<HistogramQuery>,
+) -> Result<Json<Vec<HistogramBucket>>, ApiError> {
+    let buckets = params.buckets.unwrap_or(DEFAULT_HISTOGRAM_BUCKETS);
+    if buckets == 0 || buckets > MAX_HISTOGRAM_BUCKETS {
+        return Err(ApiError::bad_request(format!(
+            "buckets must be between 1 and {}",
+            MAX_HISTOGRAM_BUCKETS
+        )));
+    }
+
+    let (min_price, max_price): (Option<f64>, Option<f64>) =
+        sqlx::query_as("SELECT MIN(price), MAX(price) FROM items")
+            .fetch_one(&state.db)
+            .await?;
+    let (Some(min_price), Some(max_price)) = (min_price, max_price) else {
+        return Ok(Json(Vec::new()));
+    };
+    let width = (max_price - min_price) / f64::from(buckets);
+
+    let counts: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT CASE WHEN ?2 = 0 THEN 0 \
+         ELSE MIN(CAST((price - ?1) / ?2 AS INTEGER), ?3 - 1) END AS bucket, COUNT(*) \
+         FROM items GROUP BY bucket",
+    )
+    .bind(min_price)
+    .bind(width)
+    .bind(buckets)
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut histogram: Vec<HistogramBucket> = (0..buckets)
+        .map(|i| HistogramBucket {
+            range_low: min_price + width * f64::from(i),
+            range_high: min_price + width * f64::from(i + 1),
+            count: 0,
+        })
+        .collect();
+    for (bucket, count) in counts {
+        if let Some(slot) = histogram.get_mut(bucket as usize) {
+            slot.count = count;
+        }
+    }
+
+    Ok(Json(histogram))
+}
+
+// Unpredictable reads for worst-case cache behaviour; ORDER BY RANDOM() scans the table
+#[utoipa::path(
+    get,
+    path = "/db/items/random",
+    tag = "items",
+    responses(
+        (status = 200, description = "A uniformly random item", body = ItemResponse),
+        (status = 404, description = "Table is empty"),
+    ),
+)]
+pub async fn random_item(
+    State(state): State<AppState>,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
+    let item: ItemResponse = sqlx::query_as(
+        "SELECT id, name, description, price, created_at, version FROM items \
+         ORDER BY RANDOM() LIMIT 1",
+    )
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("no items"))?;
+
+    Ok(format.render(&item))
+}
+
+// Newest-first tail of the table
+#[utoipa::path(
+    get,
+    path = "/db/items/recent/{n}",
+    tag = "items",
+    params(
+        ("n" = u32, Path, description = "Number of items"),
+    ),
+    responses(
+        (status = 200, description = "Newest items first", body = Vec<ItemResponse>),
+        (status = 400, description = "n out of range"),
+    ),
+)]
+pub async fn recent_items(
+    Path(n): Path<u32>,
+    State(state): State<AppState>,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
+    if n > MAX_RECENT_ITEMS {
+        return Err(ApiError::bad_request(format!(
+            "n must be at most {}",
+            MAX_RECENT_ITEMS
+        )));
+    }
+
+    let items: Vec<ItemResponse> = sqlx::query_as(
+        "SELECT id, name, description, price, created_at, version FROM items \
+         ORDER BY id DESC LIMIT ?",
+    )
+    .bind(n)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(format.render(&items))
+}
+
+#[utoipa::path(
+    get,
+    path = "/db/items/recent",
+    tag = "items",
+    responses(
+        (status = 200, description = "The 10 newest items, newest first", body = Vec<ItemResponse>),
+    ),
+)]
+pub async fn recent_items_default(
+    state: State<AppState>,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
+    recent_items(Path(DEFAULT_RECENT_ITEMS), state, format).await
+}
+
+// Makes `%`, `_` and the escape character itself match literally in a LIKE ... ESCAPE '\' pattern
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[utoipa::path(
+    get,
+    path = "/db/items/search",
+    tag = "items",
+    params(
+        ("q" = String, Query, description = "Name substring, at least 2 characters"),
+    ),
+    responses(
+        (status = 200, description = "Matching items", body = Vec<ItemResponse>),
+        (status = 400, description = "Query too short"),
+    ),
+)]
+pub async fn search_items(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<ItemResponse>>, StatusCode> {
+    let q = params.get("q").map(String::as_str).unwrap_or("");
+    if q.chars().count() < 2 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let items: Vec<ItemResponse> = sqlx::query_as(
+        "SELECT id, name, description, price, created_at, version FROM items \
+         WHERE name LIKE ? ESCAPE '\\' ORDER BY id"
+    )
+    .bind(format!("%{}%", escape_like(q)))
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        eprintln!("Database error in search_items: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(items))
+}
+
+#[utoipa::path(
+    get,
+    path = "/db/items/export.csv",
+    tag = "items",
+    responses(
+        (status = 200, description = "All items as CSV", content_type = "text/csv"),
+    ),
+)]
+pub async fn export_items_csv(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let items: Vec<CsvItemRow> =
+        sqlx::query_as("SELECT id, name, description, price, created_at FROM items ORDER BY id")
+            .fetch_all(&state.db)
+            .await?;
+
+    // Header row comes from the CsvItemRow field names, so write it by hand when empty
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    if items.is_empty() {
+        writer
+            .write_record(["id", "name", "description", "price", "created_at"])
+            .map_err(|e| ApiError::internal(format!("CSV encoding failed: {}", e)))?;
+    }
+    for item in &items {
+        writer
+            .serialize(item)
+            .map_err(|e| ApiError::internal(format!("CSV encoding failed: {}", e)))?;
+    }
+    let body = writer
+        .into_inner()
+        .map_err(|e| ApiError::internal(format!("CSV encoding failed: {}", e)))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"items.csv\""),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/db/items/{item_id}",
+    tag = "items",
+    params(
+        ("item_id" = i64, Path, description = "Item id"),
+        CurrencyQuery,
+        FieldsQuery,
+        ("pretty" = Option<bool>, Query, description = "Indent the JSON response"),
+    ),
+    responses(
+        (status = 200, description = "Item", body = ItemResponse),
+        (status = 304, description = "Matches If-None-Match"),
+        (status = 400, description = "Unknown field"),
+        (status = 404, description = "Item not found"),
+    ),
+)]
+pub async fn get_item(
+    Path(item_id): Path<i64>,
+    State(state): State<AppState>,
+    Query(currency): Query<CurrencyQuery>,
+    Query(fields): Query<FieldsQuery>,
+    headers: HeaderMap,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
+    if let Some(fields) = fields.parse()? {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM items WHERE id = ?",
+            sparse_columns(&fields)
+        ))
+        .bind(item_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("item not found"))?;
+        let item = sparse_item(&row, &fields, &currency)?;
+
+        // Hashing the trimmed body keeps the ETag specific to this field set
+        let mut hasher = DefaultHasher::new();
+        item.to_string().hash(&mut hasher);
+        let etag = format!("W/\"{:016x}\"", hasher.finish());
+        return render_with_etag(&headers, format, &etag, &item);
+    }
+
+    let mut item: ItemResponse = sqlx::query_as(
+        "SELECT id, name, description, price, created_at, version FROM items WHERE id = ?"
+    )
+    .bind(item_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("item not found"))?;
+    currency.apply(&mut item);
+
+    render_with_etag(&headers, format, &item_etag(&item), &item)
+}
+
+// 304 when If-None-Match already has `etag`, otherwise the body tagged with it
+fn render_with_etag<T: Serialize>(
+    headers: &HeaderMap,
+    format: ResponseFormat,
+    etag: &str,
+    body: &T,
+) -> Result<Response, ApiError> {
+    let etag_header =
+        header::HeaderValue::from_str(etag).map_err(|_| ApiError::internal("invalid ETag"))?;
+
+    if if_none_match(headers, etag) {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag_header)]).into_response());
+    }
+
+    let mut response = format.render(body);
+    response.headers_mut().insert(header::ETAG, etag_header);
+    Ok(response)
+}
+
+// Existence probe that never transfers the row; 200 either way
+#[utoipa::path(
+    get,
+    path = "/db/items/{item_id}/exists",
+    tag = "items",
+    params(
+        ("item_id" = i64, Path, description = "Item id"),
+    ),
+    responses(
+        (status = 200, description = "Whether the item exists", body = ItemExistsResponse),
+    ),
+)]
+pub async fn item_exists(
+    Path(item_id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<ItemExistsResponse>, ApiError> {
+    let exists = sqlx::query("SELECT 1 FROM items WHERE id = ?")
+        .bind(item_id)
+        .fetch_optional(&state.db)
+        .await?
+        .is_some();
+
+    Ok(Json(ItemExistsResponse { exists }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/db/items",
+    tag = "items",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Replays the original item on retry"),
+        CreateItemQuery,
+    ),
+    request_body = Item,
+    responses(
+        (status = 200, description = "Created item, or the would-be item for a dry run", body = ItemResponse),
+        (status = 400, description = "Validation failed"),
+        (status = 409, description = "Same Idempotency-Key still in progress"),
+    ),
+)]
+pub async fn create_item(
+    State(state): State<AppState>,
+    Query(params): Query<CreateItemQuery>,
+    headers: HeaderMap,
+    format: ResponseFormat,
+    Payload(payload): Payload<Item>,
+) -> Result<Response, ApiError> {
+    let idempotency_key = match headers.get(IDEMPOTENCY_KEY_HEADER) {
+        Some(value) => match value.to_str() {
+            Ok(key) if !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LENGTH => {
+                Some(key.to_string())
+            }
+            _ => {
+                return Err(ApiError::bad_request(format!(
+                    "Idempotency-Key must be 1-{} visible ASCII characters",
+                    MAX_IDEMPOTENCY_KEY_LENGTH
+                )))
+            }
+        },
+        None => None,
+    };
+
+    if params.dry_run {
+        return dry_run_create(payload, format);
+    }
+
+    // A retried key replays the original item, unless it has since been deleted
+    let mut reservation = None;
+    while let Some(key) = idempotency_key.as_deref() {
+        let item_id = match state.idempotency.claim(key) {
+            IdempotencyClaim::Reserved(claimed) => {
+                reservation = Some(claimed);
+                break;
+            }
+            IdempotencyClaim::InFlight => {
+                return Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    "a request with this Idempotency-Key is still in progress",
+                ))
+            }
+            IdempotencyClaim::Completed(item_id) => item_id,
+        };
+        let existing: Option<ItemResponse> = sqlx::query_as(
+            "SELECT id, name, description, price, created_at, version FROM items WHERE id = ?"
+        )
+        .bind(item_id)
+        .fetch_optional(&state.db)
+        .await?;
+        match existing {
+            Some(item) => {
+                let mut response = format.render(&item);
+                response
+                    .headers_mut()
+                    .insert("idempotent-replayed", header::HeaderValue::from_static("true"));
+                return Ok(response);
+            }
+            None => state.idempotency.forget(key, item_id),
+        }
+    }
+
+    payload.validate()?;
+
+    let result = retry_busy(state.busy_retries, || {
+        sqlx::query("INSERT INTO items (name, description, price) VALUES (?, ?, ?)")
+            .bind(&payload.name)
+            .bind(&payload.description)
+            .bind(payload.price)
+            .execute(&state.db)
+    })
+    .await?;
+
+    let item_id = result.last_insert_rowid();
+    state.invalidate_list_cache();
+    if let Some(reservation) = reservation {
+        reservation.complete(item_id);
+    }
+
+    let item: ItemResponse = sqlx::query_as(
+        "SELECT id, name, description, price, created_at, version FROM items WHERE id = ?"
+    )
+    .bind(item_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(format.render(&item))
+}
+
+// Runs create_item's checks without inserting or recording the key
+fn dry_run_create(payload: Item, format: ResponseFormat) -> Result<Response, ApiError> {
+    payload.validate()?;
+
+    Ok(format.render(&DryRunItem {
+        item: ItemResponse {
+            id: 0,
+            name: payload.name,
+            description: payload.description,
+            price: payload.price,
+            created_at: chrono::Utc::now()
+                .format(SQLITE_TIMESTAMP_FORMAT)
+                .to_string(),
+            version: 1,
+            price_display: None,
+        },
+        dry_run: true,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/db/items/bulk",
+    tag = "items",
+    request_body = Vec<Item>,
+    responses(
+        (status = 200, description = "Created items", body = Vec<ItemResponse>),
+        (status = 400, description = "Invalid item in batch"),
+    ),
+)]
+pub async fn create_items_bulk(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<Vec<Item>>,
+) -> Result<Json<Vec<ItemResponse>>, (StatusCode, Json<serde_json::Value>)> {
+    let internal_error = |e: sqlx::Error| {
+        eprintln!("Database error in create_items_bulk: {:?}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": "failed to insert items" })),
+        )
+    };
+
+    if let Some(index) = payload.iter().position(|item| item.validate().is_err()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("invalid item at index {}", index),
+                "index": index
+            })),
+        ));
+    }
+
+    // Dropping the transaction on an early return rolls back every insert
+    let mut tx = state.db.begin().await.map_err(internal_error)?;
+    let mut created = Vec::with_capacity(payload.len());
+    for item in &payload {
+        let row: ItemResponse = sqlx::query_as(
+            "INSERT INTO items (name, description, price) VALUES (?, ?, ?) \
+             RETURNING id, name, description, price, created_at, version"
+        )
+        .bind(&item.name)
+        .bind(&item.description)
+        .bind(item.price)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(internal_error)?;
+        created.push(row);
+    }
+    tx.commit().await.map_err(internal_error)?;
+    state.invalidate_list_cache();
+
+    Ok(Json(created))
+}
+
+#[utoipa::path(
+    put,
+    path = "/db/items/{item_id}",
+    tag = "items",
+    params(
+        ("item_id" = i64, Path, description = "Item id"),
+        ("If-Match" = Option<i64>, Header, description = "Expected item version"),
+    ),
+    request_body = Item,
+    responses(
+        (status = 200, description = "Updated item", body = ItemResponse),
+        (status = 400, description = "Validation failed"),
+        (status = 404, description = "Item not found"),
+        (status = 409, description = "Version does not match If-Match"),
+    ),
+)]
+pub async fn update_item(
+    Path(item_id): Path<i64>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    format: ResponseFormat,
+    Payload(payload): Payload<Item>,
+) -> Result<Response, ApiError> {
+    payload.validate()?;
+    let expected_version = if_match_version(&headers)?;
+    check_item_version(&state.db, item_id, expected_version).await?;
+
+    // The version guard catches writers that slip in after the check
+    let db = &state.db;
+    let result = retry_busy(state.busy_retries, || {
+        let mut update = QueryBuilder::<Sqlite>::new("UPDATE items SET name = ");
+        update
+            .push_bind(&payload.name)
+            .push(", description = ")
+            .push_bind(&payload.description)
+            .push(", price = ")
+            .push_bind(payload.price)
+            .push(", version = version + 1 WHERE id = ")
+            .push_bind(item_id);
+        if let Some(expected) = expected_version {
+            update.push(" AND version = ").push_bind(expected);
+        }
+        async move { update.build().execute(db).await }
+    })
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(stale_write(&state.db, item_id).await);
+    }
+    state.invalidate_list_cache();
+
+    // Get updated item
+    let item: ItemResponse = sqlx::query_as(
+        "SELECT id, name, description, price, created_at, version FROM items WHERE id = ?"
+    )
+    .bind(item_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(format.render(&item))
+}
+
+// Insert-or-update keyed on name; names aren't unique, so an update targets the oldest match
+#[utoipa::path(
+    put,
+    path = "/db/items/by-name/{name}",
+    tag = "items",
+    params(
+        ("name" = String, Path, description = "Item name; the oldest item with it is updated"),
+    ),
+    request_body = ItemUpsert,
+    responses(
+        (status = 200, description = "Inserted or updated item", body = UpsertResponse),
+        (status = 400, description = "Validation failed"),
+    ),
+)]
+pub async fn upsert_item_by_name(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    format: ResponseFormat,
+    Payload(payload): Payload<ItemUpsert>,
+) -> Result<Response, ApiError> {
+    // The path supplies the name, so validate the combined item
+    let payload = Item {
+        name,
+        description: payload.description,
+        price: payload.price,
+    };
+    payload.validate()?;
+    let name = payload.name;
+
+    // The UPDATE takes SQLite's write lock even when it matches nothing, so a concurrent
+    // upsert of the same name waits here instead of racing this one to the INSERT
+    let mut tx = state.db.begin().await?;
+    let updated: Option<ItemResponse> = sqlx::query_as(
+        "UPDATE items SET description = ?, price = ?, version = version + 1 \
+         WHERE id = (SELECT MIN(id) FROM items WHERE name = ?) \
+         RETURNING id, name, description, price, created_at, version",
+    )
+    .bind(&payload.description)
+    .bind(payload.price)
+    .bind(&name)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let created = updated.is_none();
+    let item = match updated {
+        Some(item) => item,
+        None => {
+            sqlx::query_as(
+                "INSERT INTO items (name, description, price) VALUES (?, ?, ?) \
+                 RETURNING id, name, description, price, created_at, version",
+            )
+            .bind(&name)
+            .bind(&payload.description)
+            .bind(payload.price)
+            .fetch_one(&mut *tx)
+            .await?
+        }
+    };
+    tx.commit().await?;
+    state.invalidate_list_cache();
+
+    Ok(format.render(&UpsertResponse { item, created }))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/db/items/{item_id}",
+    tag = "items",
+    params(
+        ("item_id" = i64, Path, description = "Item id"),
+        ("If-Match" = Option<i64>, Header, description = "Expected item version"),
+    ),
+    request_body = ItemPatch,
+    responses(
+        (status = 200, description = "Updated item", body = ItemResponse),
+        (status = 400, description = "Validation failed"),
+        (status = 404, description = "Item not found"),
+        (status = 409, description = "Version does not match If-Match"),
+    ),
+)]
+pub async fn patch_item(
+    Path(item_id): Path<i64>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    format: ResponseFormat,
+    Payload(payload): Payload<ItemPatch>,
+) -> Result<Response, ApiError> {
+    if payload.name.is_none() && payload.description.is_none() && payload.price.is_none() {
+        return Err(ApiError::bad_request("at least one field must be provided"));
+    }
+    payload.validate()?;
+    let expected_version = if_match_version(&headers)?;
+    check_item_version(&state.db, item_id, expected_version).await?;
+
+    // Update only the supplied fields
+    let mut update = QueryBuilder::<Sqlite>::new("UPDATE items SET ");
+    let mut assignments = update.separated(", ");
+    if let Some(name) = payload.name {
+        assignments.push("name = ").push_bind_unseparated(name);
+    }
+    if let Some(description) = payload.description {
+        assignments.push("description = ").push_bind_unseparated(description);
+    }
+    if let Some(price) = payload.price {
+        assignments.push("price = ").push_bind_unseparated(price);
+    }
+    assignments.push("version = version + 1");
+    update.push(" WHERE id = ").push_bind(item_id);
+    if let Some(expected) = expected_version {
+        update.push(" AND version = ").push_bind(expected);
+    }
+    if update.build().execute(&state.db).await?.rows_affected() == 0 {
+        return Err(stale_write(&state.db, item_id).await);
+    }
+    state.invalidate_list_cache();
+
+    // Get updated item
+    let item: ItemResponse = sqlx::query_as(
+        "SELECT id, name, description, price, created_at, version FROM items WHERE id = ?"
+    )
+    .bind(item_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(format.render(&item))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/db/items/{item_id}",
+    tag = "items",
+    params(
+        ("item_id" = i64, Path, description = "Item id"),
+    ),
+    responses(
+        (status = 200, description = "Item deleted"),
+        (status = 404, description = "Item not found"),
+    ),
+)]
+pub async fn delete_item(
+    Path(item_id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    // Check if exists
+    let existing = sqlx::query("SELECT id FROM items WHERE id = ?")
+        .bind(item_id)
+        .fetch_optional(&state.db)
+        .await?;
+
+    if existing.is_none() {
+        return Err(ApiError::not_found("item not found"));
+    }
+
+    // Delete
+    retry_busy(state.busy_retries, || {
+        sqlx::query("DELETE FROM items WHERE id = ?")
+            .bind(item_id)
+            .execute(&state.db)
+    })
+    .await?;
+    state.invalidate_list_cache();
+
+    Ok(Json(serde_json::json!({
+        "message": format!("Item {} deleted successfully", item_id)
+    })))
+}
+
+// One IN (...) query for many ids, answered in request order
+#[utoipa::path(
+    post,
+    path = "/db/items/batch-get",
+    tag = "items",
+    params(
+        BatchGetQuery,
+    ),
+    request_body = IdList,
+    responses(
+        (status = 200, description = "Items in request order; missing ids dropped or null", body = Vec<ItemResponse>),
+        (status = 400, description = "Empty or oversized id list"),
+    ),
+)]
+pub async fn batch_get_items(
+    State(state): State<AppState>,
+    Query(params): Query<BatchGetQuery>,
+    format: ResponseFormat,
+    ApiJson(payload): ApiJson<IdList>,
+) -> Result<Response, ApiError> {
+    if payload.ids.is_empty() || payload.ids.len() > MAX_BULK_IDS {
+        return Err(ApiError::bad_request(format!(
+            "ids must contain between 1 and {} entries",
+            MAX_BULK_IDS
+        )));
+    }
+
+    let mut select = QueryBuilder::<Sqlite>::new(
+        "SELECT id, name, description, price, created_at, version FROM items WHERE id IN (",
+    );
+    let mut ids = select.separated(", ");
+    for id in &payload.ids {
+        ids.push_bind(*id);
+    }
+    select.push(")");
+    let rows: Vec<ItemResponse> = select.build_query_as().fetch_all(&state.db).await?;
+
+    let by_id: HashMap<i64, ItemResponse> =
+        rows.into_iter().map(|item| (item.id, item)).collect();
+    let ordered: Vec<Option<ItemResponse>> =
+        payload.ids.iter().map(|id| by_id.get(id).cloned()).collect();
+
+    if params.include_missing {
+        Ok(format.render(&ordered))
+    } else {
+        let found: Vec<ItemResponse> = ordered.into_iter().flatten().collect();
+        Ok(format.render(&found))
+    }
+}
+
+// Wipes the whole table between benchmark runs; requires `?confirm=true`
+#[utoipa::path(
+    delete,
+    path = "/db/items",
+    tag = "items",
+    params(
+        ConfirmQuery,
+    ),
+    responses(
+        (status = 200, description = "All items deleted"),
+        (status = 400, description = "Missing confirm=true"),
+    ),
+)]
+pub async fn delete_all_items(
+    State(state): State<AppState>,
+    Query(params): Query<ConfirmQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if !params.confirm {
+        return Err(ApiError::bad_request("deleting all items requires ?confirm=true"));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let result = sqlx::query("DELETE FROM items").execute(&mut *tx).await?;
+    // Restart ids at 1 so repeated runs see the same keys
+    sqlx::query("DELETE FROM sqlite_sequence WHERE name = 'items'")
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    state.invalidate_list_cache();
+
+    Ok(Json(serde_json::json!({
+        "deleted": result.rows_affected()
+    })))
+}
+
+// One set-based UPDATE over a price range, for comparison with row-by-row PUTs
+#[utoipa::path(
+    patch,
+    path = "/db/items/bulk",
+    tag = "items",
+    request_body = BulkPriceUpdate,
+    responses(
+        (status = 200, description = "Number of items repriced", body = BulkUpdateResponse),
+        (status = 400, description = "Empty filter or non-positive multiplier"),
+    ),
+)]
+pub async fn update_items_bulk(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<BulkPriceUpdate>,
+) -> Result<Json<BulkUpdateResponse>, ApiError> {
+    let BulkPriceFilter {
+        min_price,
+        max_price,
+    } = payload.filter;
+    if min_price.is_none() && max_price.is_none() {
+        return Err(ApiError::bad_request("filter must set min_price or max_price"));
+    }
+    if let (Some(min_price), Some(max_price)) = (min_price, max_price) {
+        if min_price > max_price {
+            return Err(ApiError::bad_request("min_price must not exceed max_price"));
+        }
+    }
+    let multiplier = payload.set.price_multiplier;
+    if !(multiplier.is_finite() && multiplier > 0.0) {
+        return Err(ApiError::bad_request("price_multiplier must be positive"));
+    }
+
+    // Bumping version makes pending If-Match writes on these rows fail rather than clobber them
+    let mut update = QueryBuilder::<Sqlite>::new("UPDATE items SET price = price * ");
+    update.push_bind(multiplier).push(", version = version + 1");
+    let mut separator = " WHERE ";
+    if let Some(min_price) = min_price {
+        update.push(separator).push("price >= ").push_bind(min_price);
+        separator = " AND ";
+    }
+    if let Some(max_price) = max_price {
+        update.push(separator).push("price <= ").push_bind(max_price);
+    }
+
+    let result = update.build().execute(&state.db).await?;
+    state.invalidate_list_cache();
+
+    Ok(Json(BulkUpdateResponse {
+        rows_affected: result.rows_affected(),
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/db/items/delete",
+    tag = "items",
+    request_body = IdList,
+    responses(
+        (status = 200, description = "Requested and deleted counts"),
+        (status = 400, description = "Empty or oversized id list"),
+    ),
+)]
+pub async fn delete_items_bulk(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<IdList>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if payload.ids.is_empty() || payload.ids.len() > MAX_BULK_IDS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut delete = QueryBuilder::<Sqlite>::new("DELETE FROM items WHERE id IN (");
+    let mut ids = delete.separated(", ");
+    for id in &payload.ids {
+        ids.push_bind(*id);
+    }
+    delete.push(")");
+
+    let result = delete
+        .build()
+        .execute(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    state.invalidate_list_cache();
+
+    Ok(Json(serde_json::json!({
+        "requested": payload.ids.len(),
+        "deleted": result.rows_affected()
+    })))
+}
+
+// Stress test endpoints
+fn fib(n: u64) -> u64 {
+    if n < 2 {
+        n
+    } else {
+        fib(n - 1).wrapping_add(fib(n - 2))
+    }
+}
+
+fn is_prime(n: u64) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+}
+
+fn check_cpu_iterations(field: &str, iterations: u64, max: u64) -> Result<(), ApiError> {
+    if iterations > max {
+        return Err(ApiError::bad_request(format!(
+            "{} must be at most {}",
+            field, max
+        )));
+    }
+    Ok(())
+}
+
+// `iterations` is the loop count for mulacc/sqrt, the upper bound for prime and N for fib.
+// Loops yield every CPU_YIELD_INTERVAL steps so health checks and the request timeout
+// still get scheduled; recursive fib can't yield and runs on the blocking pool instead.
+async fn run_cpu_workload(workload: CpuWorkload, iterations: u64) -> Result<u64, StatusCode> {
+    let result = match workload {
+        CpuWorkload::Mulacc => {
+            let mut result = 0u64;
+            for i in 0..iterations {
+                result = result.wrapping_add(i.wrapping_mul(i));
+                if (i + 1).is_multiple_of(CPU_YIELD_INTERVAL) {
+                    tokio::task::yield_now().await;
+                }
+            }
+            result
+        }
+        CpuWorkload::Fib => {
+            tokio::task::spawn_blocking(move || fib(std::hint::black_box(iterations)))
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        }
+        CpuWorkload::Prime => {
+            let mut count = 0u64;
+            for n in 0..=iterations {
+                if is_prime(n) {
+                    count += 1;
+                }
+                if (n + 1).is_multiple_of(CPU_YIELD_INTERVAL) {
+                    tokio::task::yield_now().await;
+                }
+            }
+            count
+        }
+        CpuWorkload::Sqrt => {
+            let mut sum = 0f64;
+            for i in 0..iterations {
+                sum += (i as f64).sqrt();
+                if (i + 1).is_multiple_of(CPU_YIELD_INTERVAL) {
+                    tokio::task::yield_now().await;
+                }
+            }
+            sum as u64
+        }
+    };
+    Ok(result)
+}
+
+// Each request seeds its own StdRng and rolls uniformly in [0, 100); it fails when roll < rate,
+// so failures are independent with probability rate% and any response can be replayed via ?seed=
+#[utoipa::path(
+    get,
+    path = "/fail/{rate}",
+    tag = "stress",
+    params(
+        ("rate" = u8, Path, description = "Failure probability in percent (0-100)"),
+        FailQuery,
+    ),
+    responses(
+        (status = 200, description = "Roll succeeded", body = FailResponse),
+        (status = 400, description = "Rate out of range"),
+        (status = 500, description = "Injected failure", body = FailResponse),
+    ),
+)]
+pub async fn inject_failure(
+    Path(rate): Path<u8>,
+    Query(params): Query<FailQuery>,
+) -> Result<(StatusCode, Json<FailResponse>), ApiError> {
+    if rate > 100 {
+        return Err(ApiError::bad_request("rate must be between 0 and 100"));
+    }
+
+    let seed = params.seed.unwrap_or_else(rand::random);
+    let roll = rand::Rng::gen_range(&mut StdRng::seed_from_u64(seed), 0.0..100.0);
+    let failed = roll < f64::from(rate);
+    let status = if failed {
+        StatusCode::INTERNAL_SERVER_ERROR
+    } else {
+        StatusCode::OK
+    };
+
+    Ok((
+        status,
+        Json(FailResponse {
+            rate,
+            roll,
+            failed,
+            seed,
+            timestamp: current_iso_timestamp(),
+        }),
+    ))
+}
+
+// Sleeps for a per-request draw around the mean, giving percentile-aware clients a real tail;
+// draws above MAX_LATENCY_SLEEP_MS are cut to it and flagged as capped
+#[utoipa::path(
+    get,
+    path = "/latency/{mean_ms}",
+    tag = "stress",
+    params(
+        ("mean_ms" = u64, Path, description = "Mean sleep in milliseconds"),
+        LatencyQuery,
+    ),
+    responses(
+        (status = 200, description = "Drawn and actual sleep", body = LatencyResponse),
+        (status = 400, description = "Mean out of range"),
+    ),
+)]
+pub async fn jittered_latency(
+    Path(mean_ms): Path<u64>,
+    Query(params): Query<LatencyQuery>,
+) -> Result<Json<LatencyResponse>, ApiError> {
+    if mean_ms > MAX_LATENCY_MEAN_MS {
+        return Err(ApiError::bad_request(format!(
+            "mean_ms must be at most {}",
+            MAX_LATENCY_MEAN_MS
+        )));
+    }
+
+    let seed = params.seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mean = mean_ms as f64;
+    let sampled_ms = match params.dist {
+        LatencyDist::Normal => Normal::new(mean, mean / 4.0)
+            .map_err(|e| ApiError::internal(e.to_string()))?
+            .sample(&mut rng),
+        LatencyDist::Exponential if mean_ms == 0 => 0.0,
+        LatencyDist::Exponential => Exp::new(1.0 / mean)
+            .map_err(|e| ApiError::internal(e.to_string()))?
+            .sample(&mut rng),
+    }
+    .max(0.0);
+    let capped = sampled_ms > MAX_LATENCY_SLEEP_MS;
+
+    let start = Instant::now();
+    sleep(Duration::from_secs_f64(sampled_ms.min(MAX_LATENCY_SLEEP_MS) / 1000.0)).await;
+
+    Ok(Json(LatencyResponse {
+        dist: params.dist,
+        mean_ms,
+        sampled_ms,
+        slept_ms: start.elapsed().as_secs_f64() * 1000.0,
+        capped,
+        seed,
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/stress/cpu/{iterations}",
+    tag = "stress",
+    params(
+        ("iterations" = u64, Path, description = "Workload iterations"),
+        CpuStressQuery,
+    ),
+    responses(
+        (status = 200, description = "Workload result", body = CpuStressResponse),
+        (status = 400, description = "Iterations out of range"),
+    ),
+)]
+pub async fn cpu_stress(
+    Path(iterations): Path<u64>,
+    Query(params): Query<CpuStressQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<CpuStressResponse>, ApiError> {
+    check_cpu_iterations("iterations", iterations, state.max_cpu_iterations)?;
+    if matches!(params.workload, CpuWorkload::Fib) && iterations > MAX_FIB_N {
+        return Err(ApiError::bad_request(format!(
+            "fib input must be at most {}",
+            MAX_FIB_N
+        )));
+    }
+
+    let start = Instant::now();
+    let result = run_cpu_workload(params.workload, iterations)
+        .await
+        .map_err(|status| ApiError::new(status, "cpu workload failed"))?;
+    let processing_time = start.elapsed().as_secs_f64() * 1000.0;
+    
+    Ok(Json(CpuStressResponse {
+        workload: params.workload,
+        iterations,
+        result,
+        processing_time_ms: processing_time,
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+// Fans out `tasks` small CPU bursts across the runtime and waits for all of them
+#[utoipa::path(
+    get,
+    path = "/stress/concurrency/{tasks}",
+    tag = "stress",
+    params(
+        ("tasks" = u32, Path, description = "Number of concurrent tasks"),
+    ),
+    responses(
+        (status = 200, description = "Fan-out timing"),
+        (status = 400, description = "Task count out of range"),
+    ),
+)]
+pub async fn concurrency_stress(Path(tasks): Path<u32>) -> Result<Json<serde_json::Value>, StatusCode> {
+    if tasks > MAX_CONCURRENCY_TASKS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let start = Instant::now();
+    let handles = (0..tasks).map(|task| {
+        tokio::spawn(async move {
+            let task_start = Instant::now();
+            let mut result = u64::from(task);
+            for i in 0..CONCURRENCY_TASK_ITERATIONS {
+                result = result.wrapping_add(i.wrapping_mul(i));
+            }
+            std::hint::black_box(result);
+            task_start.elapsed().as_secs_f64() * 1000.0
+        })
+    });
+    let task_times = join_all(handles)
+        .await
+        .into_iter()
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let total_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    let avg_task_ms = if task_times.is_empty() {
+        0.0
+    } else {
+        task_times.iter().sum::<f64>() / task_times.len() as f64
+    };
+
+    Ok(Json(serde_json::json!({
+        "tasks": tasks,
+        "total_ms": total_ms,
+        "avg_task_ms": avg_task_ms,
+        "timestamp": current_iso_timestamp()
+    })))
+}
+
+// Re-parses and re-serializes the body in a blocking task, isolating serde_json cost from I/O
+#[utoipa::path(
+    post,
+    path = "/stress/json/{iterations}",
+    tag = "stress",
+    params(
+        ("iterations" = u64, Path, description = "Parse/serialize round trips"),
+    ),
+    request_body(content = Object, description = "Any JSON document"),
+    responses(
+        (status = 200, description = "Round-trip timing", body = JsonStressResponse),
+        (status = 400, description = "Invalid JSON or iterations out of range"),
+    ),
+)]
+pub async fn json_stress(
+    Path(iterations): Path<u64>,
+    body: Bytes,
+) -> Result<Json<JsonStressResponse>, ApiError> {
+    if iterations > MAX_JSON_STRESS_ITERATIONS {
+        return Err(ApiError::bad_request(format!(
+            "iterations must be at most {}",
+            MAX_JSON_STRESS_ITERATIONS
+        )));
+    }
+    serde_json::from_slice::<serde_json::Value>(&body)
+        .map_err(|e| ApiError::bad_request(format!("invalid JSON body: {}", e)))?;
+
+    let payload_bytes = body.len();
+    let elapsed = tokio::task::spawn_blocking(move || {
+        let start = Instant::now();
+        let mut buffer = body.to_vec();
+        for _ in 0..iterations {
+            let value: serde_json::Value =
+                serde_json::from_slice(std::hint::black_box(&buffer)).expect("validated above");
+            buffer = serde_json::to_vec(&value).expect("Value always serializes");
+        }
+        std::hint::black_box(buffer);
+        start.elapsed()
+    })
+    .await
+    .map_err(|_| ApiError::internal("JSON stress task failed"))?;
+
+    let seconds = elapsed.as_secs_f64();
+    Ok(Json(JsonStressResponse {
+        iterations,
+        payload_bytes,
+        processing_time_ms: seconds * 1000.0,
+        parses_per_second: if seconds > 0.0 { iterations as f64 / seconds } else { 0.0 },
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+// Compiles once, then counts matches over the haystack `iterations` times in a blocking task
+#[utoipa::path(
+    post,
+    path = "/stress/regex",
+    tag = "stress",
+    request_body = RegexStressRequest,
+    responses(
+        (status = 200, description = "Match timing", body = RegexStressResponse),
+        (status = 400, description = "Invalid pattern or iterations out of range"),
+    ),
+)]
+pub async fn regex_stress(
+    ApiJson(payload): ApiJson<RegexStressRequest>,
+) -> Result<Json<RegexStressResponse>, ApiError> {
+    if payload.iterations > MAX_REGEX_STRESS_ITERATIONS {
+        return Err(ApiError::bad_request(format!(
+            "iterations must be at most {}",
+            MAX_REGEX_STRESS_ITERATIONS
+        )));
+    }
+
+    let compile_start = Instant::now();
+    let regex = Regex::new(&payload.pattern)
+        .map_err(|e| ApiError::bad_request(format!("invalid pattern: {}", e)))?;
+    let compile_ms = compile_start.elapsed().as_secs_f64() * 1000.0;
+
+    let iterations = payload.iterations;
+    let haystack = payload.haystack;
+    let (total_matches, elapsed) = tokio::task::spawn_blocking(move || {
+        let start = Instant::now();
+        let mut total = 0u64;
+        for _ in 0..iterations {
+            total += regex.find_iter(std::hint::black_box(&haystack)).count() as u64;
+        }
+        (total, start.elapsed())
+    })
+    .await
+    .map_err(|_| ApiError::internal("regex stress task failed"))?;
+
+    Ok(Json(RegexStressResponse {
+        iterations,
+        matches_per_iteration: total_matches.checked_div(iterations).unwrap_or(0),
+        total_matches,
+        compile_ms,
+        processing_time_ms: elapsed.as_secs_f64() * 1000.0,
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/stress/memory/{size_mb}",
+    tag = "stress",
+    params(
+        ("size_mb" = u64, Path, description = "Megabytes to allocate"),
+        MemoryStressQuery,
+    ),
+    responses(
+        (status = 200, description = "Allocation timing", body = MemoryStressResponse),
+        (status = 400, description = "Size or hold out of range"),
+    ),
+)]
+pub async fn memory_stress(
+    Path(size_mb): Path<u64>,
+    Query(params): Query<MemoryStressQuery>,
+) -> Result<Json<MemoryStressResponse>, StatusCode> {
+    if size_mb > MAX_STRESS_SIZE_MB || params.hold_ms > MAX_MEMORY_HOLD_MS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    
+    let start = Instant::now();
+    let allocated_bytes = run_memory_workload(size_mb, params.hold_ms).await;
+    let processing_time = start.elapsed().as_secs_f64() * 1000.0;
+    
+    Ok(Json(MemoryStressResponse {
+        allocated_bytes,
+        allocated_mb: size_mb,
+        hold_ms: params.hold_ms,
+        processing_time_ms: processing_time,
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+async fn run_memory_workload(size_mb: u64, hold_ms: u64) -> usize {
+    let size_bytes = (size_mb * 1024 * 1024) as usize;
+    let mut data = vec![0u8; size_bytes];
+    // Write one byte per page so the OS actually commits the memory
+    for page in data.iter_mut().step_by(4096) {
+        *page = 1;
+    }
+    let data = std::hint::black_box(data);
+    let allocated_bytes = data.len();
+    if hold_ms > 0 {
+        sleep(Duration::from_millis(hold_ms)).await;
+    }
+    allocated_bytes
+}
+
+// CPU, memory and select workloads back to back, as one reproducible run
+#[utoipa::path(
+    post,
+    path = "/bench/run",
+    tag = "stress",
+    request_body = BenchRunRequest,
+    responses(
+        (status = 200, description = "Combined report", body = BenchRunResponse),
+        (status = 400, description = "Parameter out of range"),
+    ),
+)]
+pub async fn bench_run(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<BenchRunRequest>,
+) -> Result<Json<BenchRunResponse>, ApiError> {
+    check_cpu_iterations(
+        "cpu_iterations",
+        payload.cpu_iterations,
+        state.max_cpu_iterations,
+    )?;
+    if payload.memory_mb > MAX_STRESS_SIZE_MB {
+        return Err(ApiError::bad_request(format!(
+            "memory_mb must not exceed {}",
+            MAX_STRESS_SIZE_MB
+        )));
+    }
+
+    let start = Instant::now();
+
+    let cpu_start = Instant::now();
+    let result = run_cpu_workload(CpuWorkload::Mulacc, payload.cpu_iterations)
+        .await
+        .map_err(|status| ApiError::new(status, "cpu workload failed"))?;
+    let cpu = CpuStressResponse {
+        workload: CpuWorkload::Mulacc,
+        iterations: payload.cpu_iterations,
+        result,
+        processing_time_ms: cpu_start.elapsed().as_secs_f64() * 1000.0,
+        timestamp: current_iso_timestamp(),
+    };
+
+    let memory_start = Instant::now();
+    let allocated_bytes = run_memory_workload(payload.memory_mb, 0).await;
+    let memory = MemoryStressResponse {
+        allocated_bytes,
+        allocated_mb: payload.memory_mb,
+        hold_ms: 0,
+        processing_time_ms: memory_start.elapsed().as_secs_f64() * 1000.0,
+        timestamp: current_iso_timestamp(),
+    };
+
+    let select_start = Instant::now();
+    let rows_fetched = select_items(&state.db, payload.db_select_count).await?;
+    let db_select = SelectBenchmarkResult {
+        rows_fetched,
+        processing_time_ms: select_start.elapsed().as_secs_f64() * 1000.0,
+    };
+
+    Ok(Json(BenchRunResponse {
+        cpu,
+        memory,
+        db_select,
+        total_time_ms: start.elapsed().as_secs_f64() * 1000.0,
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+// Writes, fsyncs, reads back and removes a temp file of `size_mb` random megabytes
+#[utoipa::path(
+    get,
+    path = "/stress/disk/{size_mb}",
+    tag = "stress",
+    params(
+        ("size_mb" = u64, Path, description = "Megabytes to write and read back"),
+    ),
+    responses(
+        (status = 200, description = "Disk round-trip timing", body = DiskStressResponse),
+        (status = 400, description = "Size out of range"),
+    ),
+)]
+pub async fn disk_stress(Path(size_mb): Path<u64>) -> Result<Json<DiskStressResponse>, StatusCode> {
+    static FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    if size_mb > MAX_STRESS_SIZE_MB {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut chunk = vec![0u8; 1024 * 1024];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut chunk);
+    let path = std::env::temp_dir().join(format!(
+        "axum-benchmark-disk-{}-{}.tmp",
+        std::process::id(),
+        FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let start = Instant::now();
+    let result = disk_round_trip(&path, &chunk, size_mb).await;
+    let _ = tokio::fs::remove_file(&path).await;
+    let (bytes_written, write_ms, read_ms) = result.map_err(|e| {
+        eprintln!("I/O error in disk_stress: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let processing_time = start.elapsed().as_secs_f64() * 1000.0;
+
+    Ok(Json(DiskStressResponse {
+        bytes_written,
+        write_ms,
+        read_ms,
+        processing_time_ms: processing_time,
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+async fn disk_round_trip(
+    path: &std::path::Path,
+    chunk: &[u8],
+    size_mb: u64,
+) -> std::io::Result<(u64, f64, f64)> {
+    let write_start = Instant::now();
+    let mut file = tokio::fs::File::create(path).await?;
+    let mut bytes_written = 0u64;
+    for _ in 0..size_mb {
+        file.write_all(chunk).await?;
+        bytes_written += chunk.len() as u64;
+    }
+    file.sync_all().await?;
+    drop(file);
+    let write_ms = write_start.elapsed().as_secs_f64() * 1000.0;
+
+    let read_start = Instant::now();
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut buffer = vec![0u8; chunk.len()];
+    while file.read(&mut buffer).await? > 0 {}
+    let read_ms = read_start.elapsed().as_secs_f64() * 1000.0;
+
+    Ok((bytes_written, write_ms, read_ms))
+}
+
+// Pragmas are per-connection, so read them all from the same pooled connection.
+// Some (e.g. mmap_size) return no row for in-memory databases and come back as null.
+#[utoipa::path(
+    get,
+    path = "/db/pragmas",
+    tag = "database",
+    responses(
+        (status = 200, description = "Active SQLite pragmas", body = PragmaResponse),
+    ),
+)]
+pub async fn db_pragmas(State(state): State<AppState>) -> Result<Json<PragmaResponse>, ApiError> {
+    let mut conn = state.db.acquire().await?;
+
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(&mut *conn)
+        .await?;
+    let synchronous: Option<i64> = sqlx::query_scalar("PRAGMA synchronous")
+        .fetch_optional(&mut *conn)
+        .await?;
+    let cache_size: Option<i64> = sqlx::query_scalar("PRAGMA cache_size")
+        .fetch_optional(&mut *conn)
+        .await?;
+    let mmap_size: Option<i64> = sqlx::query_scalar("PRAGMA mmap_size")
+        .fetch_optional(&mut *conn)
+        .await?;
+    let page_size: Option<i64> = sqlx::query_scalar("PRAGMA page_size")
+        .fetch_optional(&mut *conn)
+        .await?;
+    let temp_store: Option<i64> = sqlx::query_scalar("PRAGMA temp_store")
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    Ok(Json(PragmaResponse {
+        journal_mode,
+        synchronous,
+        cache_size,
+        mmap_size,
+        page_size,
+        temp_store,
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/db/pool",
+    tag = "database",
+    responses(
+        (status = 200, description = "Connection pool statistics", body = PoolStatsResponse),
+    ),
+)]
+pub async fn db_pool(State(state): State<AppState>) -> Json<PoolStatsResponse> {
+    let options = state.db.options();
+    Json(PoolStatsResponse {
+        size: state.db.size(),
+        idle: state.db.num_idle(),
+        max: options.get_max_connections(),
+        min: options.get_min_connections(),
+        timestamp: current_iso_timestamp(),
+    })
+}
+
+// Times only connection acquire + SELECT 1, isolating DB latency from handler work
+#[utoipa::path(
+    get,
+    path = "/db/ping",
+    tag = "database",
+    responses(
+        (status = 200, description = "Database round-trip time", body = DbPingResponse),
+    ),
+)]
+pub async fn db_ping(State(state): State<AppState>) -> Result<Json<DbPingResponse>, ApiError> {
+    let start = Instant::now();
+    let mut conn = state.db.acquire().await?;
+    sqlx::query("SELECT 1").execute(&mut *conn).await?;
+    let roundtrip_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    Ok(Json(DbPingResponse {
+        roundtrip_ms,
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+// Flushes and truncates the -wal file between benchmark phases
+#[utoipa::path(
+    post,
+    path = "/db/checkpoint",
+    tag = "database",
+    responses(
+        (status = 200, description = "WAL checkpoint result", body = CheckpointResponse),
+    ),
+)]
+pub async fn db_checkpoint(
+    State(state): State<AppState>,
+) -> Result<Json<CheckpointResponse>, ApiError> {
+    let (busy, log_frames, checkpointed_frames): (i64, i64, i64) =
+        sqlx::query_as("PRAGMA wal_checkpoint(TRUNCATE)")
+            .fetch_one(&state.db)
+            .await?;
+
+    Ok(Json(CheckpointResponse {
+        busy,
+        log_frames,
+        checkpointed_frames,
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+// Refreshes planner statistics between benchmark phases; admin-only since ANALYZE scans every index
+#[utoipa::path(
+    post,
+    path = "/db/analyze",
+    tag = "database",
+    params(
+        ("x-admin-token" = String, Header, description = "Must match ADMIN_TOKEN"),
+    ),
+    responses(
+        (status = 200, description = "Statistics refreshed", body = AnalyzeResponse),
+        (status = 401, description = "Missing x-admin-token"),
+        (status = 403, description = "Wrong x-admin-token"),
+        (status = 404, description = "ADMIN_TOKEN is unset"),
+    ),
+)]
+pub async fn db_analyze(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<AnalyzeResponse>, ApiError> {
+    require_admin(&state, &headers)?;
+
+    let start = Instant::now();
+    let mut conn = state.db.acquire().await?;
+    sqlx::query("ANALYZE").execute(&mut *conn).await?;
+    sqlx::query("PRAGMA optimize").execute(&mut *conn).await?;
+
+    Ok(Json(AnalyzeResponse {
+        processing_time_ms: start.elapsed().as_secs_f64() * 1000.0,
+        timestamp: current_iso_timestamp(),
+    }))
+}
+
+// Only a single SELECT statement is planned; EXPLAIN QUERY PLAN never executes it
+#[utoipa::path(
+    get,
+    path = "/db/explain",
+    tag = "database",
+    params(
+        ExplainQuery,
+    ),
+    responses(
+        (status = 200, description = "Query plan rows"),
+        (status = 400, description = "Not a single SELECT, or planning failed"),
+    ),
+)]
+pub async fn db_explain(
+    State(state): State<AppState>,
+    Query(params): Query<ExplainQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let sql = params.sql.trim().trim_end_matches(';').trim_end();
+    let is_select = sql
+        .get(..6)
+        .is_some_and(|keyword| keyword.eq_ignore_ascii_case("select"));
+    if !is_select || sql.contains(';') {
+        return Err(ApiError::bad_request(
+            "only a single SELECT statement can be explained",
+        ));
+    }
+
+    let plan: Vec<QueryPlanRow> = sqlx::query_as(&format!("EXPLAIN QUERY PLAN {}", sql))
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| ApiError::bad_request(format!("could not plan query: {}", e)))?;
+
+    Ok(Json(serde_json::json!({
+        "sql": sql,
+        "plan": plan,
+        "timestamp": current_iso_timestamp()
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/db/benchmark/select/{count}",
+    tag = "database",
+    params(
+        ("count" = u32, Path, description = "Number of selects"),
+        SelectBenchmarkQuery,
+    ),
+    responses(
+        (status = 200, description = "Select timing"),
+        (status = 400, description = "extra_delay_ms out of range"),
+    ),
+)]
+pub async fn db_benchmark_select(
+    Path(count): Path<u32>,
+    Query(params): Query<SelectBenchmarkQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if params.extra_delay_ms > MAX_ECHO_DELAY_MS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let start = Instant::now();
+    
+    let rows_fetched = select_items(&state.db, count)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let query_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    // Stands in for downstream work layered on top of the read
+    if params.extra_delay_ms > 0 {
+        sleep(Duration::from_millis(params.extra_delay_ms)).await;
+    }
+    let total_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    // processing_time_ms stays the end-to-end figure, matching the Python server
+    Ok(Json(serde_json::json!({
+        "rows_fetched": rows_fetched,
+        "query_ms": query_ms,
+        "total_ms": total_ms,
+        "processing_time_ms": total_ms,
+        "timestamp": current_iso_timestamp()
+    })))
 }
 
-pub async fn health_check(State(state): State<AppState>) -> Json<HealthResponse> {
-    let db_status = match sqlx::query("SELECT 1").fetch_one(&state.db).await {
-        Ok(_) => "connected",
-        Err(_) => "disconnected",
-    };
-
-    Json(HealthResponse {
-        status: "healthy".to_string(),
-        timestamp: current_iso_timestamp(),
-        database: db_status.to_string(),
-    })
+async fn select_items(db: &SqlitePool, count: u32) -> Result<usize, sqlx::Error> {
+    let rows = sqlx::query("SELECT id, name, description, price FROM items LIMIT ?")
+        .bind(count)
+        .fetch_all(db)
+        .await?;
+    Ok(rows.len())
 }
 
-pub async fn echo_post(Json(payload): Json<EchoRequest>) -> Json<EchoResponse> {
+// Deliberate N+1: one round trip per row instead of a single batched SELECT
+#[utoipa::path(
+    get,
+    path = "/db/benchmark/nplus1/{count}",
+    tag = "database",
+    params(
+        ("count" = u32, Path, description = "Number of single-row selects"),
+    ),
+    responses(
+        (status = 200, description = "Sequential select timing"),
+        (status = 400, description = "Count out of range"),
+    ),
+)]
+pub async fn db_benchmark_nplus1(
+    Path(count): Path<u32>,
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if count > MAX_NPLUS1_QUERIES {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let (min_id, max_id): (Option<i64>, Option<i64>) =
+        sqlx::query_as("SELECT MIN(id), MAX(id) FROM items")
+            .fetch_one(&state.db)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let (min_id, max_id) = (min_id.unwrap_or(0), max_id.unwrap_or(0));
+
     let start = Instant::now();
-    sleep(Duration::from_millis(1)).await;
+    let mut rows_found = 0u32;
+    for _ in 0..count {
+        let id = rand::Rng::gen_range(&mut rand::thread_rng(), min_id..=max_id);
+        let row = sqlx::query("SELECT id, name, description, price FROM items WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        if row.is_some() {
+            rows_found += 1;
+        }
+    }
     let processing_time = start.elapsed().as_secs_f64() * 1000.0;
-    
-    Json(EchoResponse {
-        message: payload.message,
-        data: payload.data,
-        timestamp: current_iso_timestamp(),
-        processing_time_ms: processing_time,
-    })
+    let per_query = if count > 0 { processing_time / count as f64 } else { 0.0 };
+
+    Ok(Json(serde_json::json!({
+        "queries": count,
+        "rows_found": rows_found,
+        "processing_time_ms": processing_time,
+        "per_query_ms": per_query,
+        "timestamp": current_iso_timestamp()
+    })))
 }
 
-pub async fn echo_get(Path(message): Path<String>) -> Json<serde_json::Value> {
-    let start = Instant::now();
-    sleep(Duration::from_millis(1)).await;
-    let processing_time = start.elapsed().as_secs_f64() * 1000.0;
-    
-    Json(serde_json::json!({
-        "message": message,
-        "timestamp": current_iso_timestamp(),
-        "processing_time_ms": processing_time
-    }))
+// Starts a background seed of `count` rows named seed_<job>_<i> and returns at once.
+// Batches commit individually, so progress and the rows themselves are visible while it runs.
+#[utoipa::path(
+    post,
+    path = "/db/seed/{count}",
+    tag = "database",
+    params(
+        ("count" = u32, Path, description = "Rows to insert"),
+    ),
+    responses(
+        (status = 202, description = "Seed job started", body = SeedJobStarted),
+        (status = 400, description = "Count out of range"),
+    ),
+)]
+pub async fn start_seed_job(
+    Path(count): Path<u32>,
+    State(state): State<AppState>,
+) -> Result<(StatusCode, Json<SeedJobStarted>), ApiError> {
+    if count > MAX_SEED_JOB_ROWS {
+        return Err(ApiError::bad_request(format!(
+            "count must be at most {}",
+            MAX_SEED_JOB_ROWS
+        )));
+    }
+
+    let job_id = state.seed_jobs.start(count);
+    tokio::spawn(run_seed_job(state, job_id, count));
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(SeedJobStarted {
+            job_id,
+            total: count,
+        }),
+    ))
 }
 
-// Database CRUD operations - NO COMPILE-TIME MACROS
-pub async fn get_all_items(State(state): State<AppState>) -> Result<Json<Vec<ItemResponse>>, StatusCode> {
-    let items: Vec<ItemResponse> = sqlx::query_as(
-        "SELECT id, name, description, price, created_at FROM items ORDER BY id"
-    )
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| {
-        eprintln!("Database error in get_all_items: {:?}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+async fn run_seed_job(state: AppState, job_id: Uuid, count: u32) {
+    let prefix = format!("seed_{}", job_id.simple());
+    let mut error = None;
+    // Batch ids are built lazily so a ten-million-row job doesn't hold them all at once
+    for first in (1..=count).step_by(SEED_BATCH_ROWS) {
+        let chunk: Vec<u32> = (first..=count).take(SEED_BATCH_ROWS).collect();
+        let result = seed_batch_insert(&prefix, &chunk)
+            .build()
+            .execute(&state.db)
+            .await;
+        if let Err(e) = result {
+            eprintln!("Seed job {} failed: {:?}", job_id, e);
+            error = Some(e.to_string());
+            break;
+        }
+        state.invalidate_list_cache();
+        state.seed_jobs.record_batch(job_id, chunk.len() as u32);
+    }
 
-    Ok(Json(items))
+    state.seed_jobs.finish(job_id, error);
 }
 
-pub async fn get_item(
-    Path(item_id): Path<i64>,
+#[utoipa::path(
+    get,
+    path = "/db/seed/{job_id}",
+    tag = "database",
+    params(
+        ("job_id" = String, Path, description = "Id returned by POST /db/seed/{count}"),
+    ),
+    responses(
+        (status = 200, description = "Seed job progress", body = SeedProgress),
+        (status = 404, description = "Unknown job"),
+    ),
+)]
+pub async fn seed_job_progress(
+    Path(job_id): Path<Uuid>,
     State(state): State<AppState>,
-) -> Result<Json<ItemResponse>, StatusCode> {
-    let item: ItemResponse = sqlx::query_as(
-        "SELECT id, name, description, price, created_at FROM items WHERE id = ?"
-    )
-    .bind(item_id)
-    .fetch_one(&state.db)
-    .await
-    .map_err(|_| StatusCode::NOT_FOUND)?;
-
-    Ok(Json(item))
+) -> Result<Json<SeedProgress>, ApiError> {
+    state
+        .seed_jobs
+        .get(job_id)
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("seed job not found"))
 }
 
-pub async fn create_item(
+// Inserted rows are namespaced as bench_item_<run>_* and removed once timing is captured
+#[utoipa::path(
+    post,
+    path = "/db/benchmark/insert/{count}",
+    tag = "database",
+    params(
+        ("count" = u32, Path, description = "Rows to insert"),
+    ),
+    responses(
+        (status = 200, description = "Insert timing"),
+        (status = 400, description = "Count out of range"),
+    ),
+)]
+pub async fn db_benchmark_insert(
+    Path(count): Path<u32>,
     State(state): State<AppState>,
-    Json(payload): Json<Item>,
-) -> Result<Json<ItemResponse>, StatusCode> {
-    if payload.name.is_empty() || payload.price < 0.0 {
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if count > MAX_INSERT_BENCHMARK_ROWS {
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    let result = sqlx::query("INSERT INTO items (name, description, price) VALUES (?, ?, ?)")
-        .bind(&payload.name)
-        .bind(&payload.description)
-        .bind(payload.price)
-        .execute(&state.db)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let run = Uuid::new_v4().simple();
+    let start = Instant::now();
 
-    let item_id = result.last_insert_rowid();
+    let mut tx = state.db.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let mut inserted_ids = None;
+    for i in 0..count {
+        let result = sqlx::query("INSERT INTO items (name, description, price) VALUES (?, ?, ?)")
+            .bind(format!("bench_item_{}_{}", run, i))
+            .bind("insert benchmark row")
+            .bind(random_price())
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let id = result.last_insert_rowid();
+        inserted_ids = Some(inserted_ids.map_or((id, id), |(first, _)| (first, id)));
+    }
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    state.invalidate_list_cache();
 
-    let item: ItemResponse = sqlx::query_as(
-        "SELECT id, name, description, price, created_at FROM items WHERE id = ?"
-    )
-    .bind(item_id)
-    .fetch_one(&state.db)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let elapsed = start.elapsed().as_secs_f64();
+
+    if let Some((first, last)) = inserted_ids {
+        sqlx::query("DELETE FROM items WHERE id BETWEEN ? AND ? AND name LIKE 'bench_item_%'")
+            .bind(first)
+            .bind(last)
+            .execute(&state.db)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        state.invalidate_list_cache();
+    }
+
+    let rows_per_second = if elapsed > 0.0 { count as f64 / elapsed } else { 0.0 };
 
-    Ok(Json(item))
+    Ok(Json(serde_json::json!({
+        "rows_inserted": count,
+        "processing_time_ms": elapsed * 1000.0,
+        "rows_per_second": rows_per_second,
+        "timestamp": current_iso_timestamp()
+    })))
 }
 
-pub async fn update_item(
-    Path(item_id): Path<i64>,
+// Each iteration is its own insert + delete transaction, so the table is left unchanged
+#[utoipa::path(
+    get,
+    path = "/db/benchmark/transactions/{count}",
+    tag = "database",
+    params(
+        ("count" = u32, Path, description = "Number of transactions"),
+    ),
+    responses(
+        (status = 200, description = "Transaction timing"),
+        (status = 400, description = "Count out of range"),
+    ),
+)]
+pub async fn db_benchmark_transactions(
+    Path(count): Path<u32>,
     State(state): State<AppState>,
-    Json(payload): Json<Item>,
-) -> Result<Json<ItemResponse>, StatusCode> {
-    if payload.name.is_empty() || payload.price < 0.0 {
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if count > MAX_TRANSACTION_BENCHMARK_COUNT {
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    // Check if exists
-    let existing = sqlx::query("SELECT id FROM items WHERE id = ?")
-        .bind(item_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    // Per-run prefix so cleanup never touches a concurrent run's rows
+    let run = Uuid::new_v4().simple();
+    let start = Instant::now();
 
-    if existing.is_none() {
-        return Err(StatusCode::NOT_FOUND);
+    for i in 0..count {
+        let mut tx = state.db.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let result = sqlx::query("INSERT INTO items (name, description, price) VALUES (?, ?, ?)")
+            .bind(format!("bench_txn_{}_{}", run, i))
+            .bind("transaction benchmark row")
+            .bind(random_price())
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        sqlx::query("DELETE FROM items WHERE id = ?")
+            .bind(result.last_insert_rowid())
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     }
+    state.invalidate_list_cache();
 
-    // Update
-    sqlx::query("UPDATE items SET name = ?, description = ?, price = ? WHERE id = ?")
-        .bind(&payload.name)
-        .bind(&payload.description)
-        .bind(payload.price)
-        .bind(item_id)
-        .execute(&state.db)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    // Get updated item
-    let item: ItemResponse = sqlx::query_as(
-        "SELECT id, name, description, price, created_at FROM items WHERE id = ?"
-    )
-    .bind(item_id)
-    .fetch_one(&state.db)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let total_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let avg_ms_per_txn = if count > 0 { total_ms / count as f64 } else { 0.0 };
 
-    Ok(Json(item))
+    Ok(Json(serde_json::json!({
+        "transactions": count,
+        "total_ms": total_ms,
+        "avg_ms_per_txn": avg_ms_per_txn,
+        "timestamp": current_iso_timestamp()
+    })))
 }
 
-pub async fn delete_item(
-    Path(item_id): Path<i64>,
+// All writers start together and queue on SQLite's single write lock; rows are removed afterwards
+#[utoipa::path(
+    get,
+    path = "/db/benchmark/concurrent-writes/{writers}",
+    tag = "database",
+    params(
+        ("writers" = u32, Path, description = "Concurrent inserting tasks"),
+    ),
+    responses(
+        (status = 200, description = "Write contention timing"),
+        (status = 400, description = "Writers out of range"),
+    ),
+)]
+pub async fn db_benchmark_concurrent_writes(
+    Path(writers): Path<u32>,
     State(state): State<AppState>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    // Check if exists
-    let existing = sqlx::query("SELECT id FROM items WHERE id = ?")
-        .bind(item_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    if existing.is_none() {
-        return Err(StatusCode::NOT_FOUND);
+    if writers > MAX_CONCURRENT_WRITERS {
+        return Err(StatusCode::BAD_REQUEST);
     }
 
-    // Delete
-    sqlx::query("DELETE FROM items WHERE id = ?")
-        .bind(item_id)
+    let run = Uuid::new_v4().simple();
+    let barrier = Arc::new(tokio::sync::Barrier::new(writers as usize));
+    let start = Instant::now();
+    let handles = (0..writers).map(|i| {
+        let db = state.db.clone();
+        let barrier = barrier.clone();
+        tokio::spawn(async move {
+            barrier.wait().await;
+            let write_start = Instant::now();
+            let mut retries = 0u32;
+            loop {
+                let result =
+                    sqlx::query("INSERT INTO items (name, description, price) VALUES (?, ?, ?)")
+                        .bind(format!("bench_write_{}_{}", run, i))
+                        .bind("concurrent write benchmark row")
+                        .bind(random_price())
+                        .execute(&db)
+                        .await;
+                match result {
+                    Ok(_) => break,
+                    Err(e) if is_busy(&e) && retries < MAX_BUSY_RETRIES => {
+                        retries += 1;
+                        sleep(Duration::from_millis(u64::from(retries))).await;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok((write_start.elapsed().as_secs_f64() * 1000.0, retries))
+        })
+    });
+    let results = join_all(handles).await;
+    let total_ms = start.elapsed().as_secs_f64() * 1000.0;
+    state.invalidate_list_cache();
+
+    sqlx::query("DELETE FROM items WHERE name LIKE ?")
+        .bind(format!("bench_write_{}_%", run))
         .execute(&state.db)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    state.invalidate_list_cache();
+
+    let mut write_times = Vec::with_capacity(results.len());
+    let mut busy_retries = 0u32;
+    let mut failed = 0u32;
+    for result in results {
+        match result.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)? {
+            Ok((elapsed_ms, retries)) => {
+                write_times.push(elapsed_ms);
+                busy_retries += retries;
+            }
+            Err(e) => {
+                eprintln!("Concurrent write failed: {:?}", e);
+                failed += 1;
+            }
+        }
+    }
+    let avg_write_ms = if write_times.is_empty() {
+        0.0
+    } else {
+        write_times.iter().sum::<f64>() / write_times.len() as f64
+    };
+    let max_write_ms = write_times.iter().copied().fold(0.0, f64::max);
 
     Ok(Json(serde_json::json!({
-        "message": format!("Item {} deleted successfully", item_id)
+        "writers": writers,
+        "succeeded": write_times.len(),
+        "failed": failed,
+        "busy_retries": busy_retries,
+        "total_ms": total_ms,
+        "avg_write_ms": avg_write_ms,
+        "max_write_ms": max_write_ms,
+        "timestamp": current_iso_timestamp()
     })))
 }
 
-// Stress test endpoints
-pub async fn cpu_stress(Path(iterations): Path<u64>) -> Json<CpuStressResponse> {
-    let start = Instant::now();
-    let mut result = 0u64;
-    for i in 0..iterations {
-        result = result.wrapping_add(i.wrapping_mul(i));
+// Admin routes 404 while ADMIN_TOKEN is unset, so they can't be discovered
+fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<(), ApiError> {
+    let Some(admin_token) = &state.admin_token else {
+        return Err(ApiError::not_found("not found"));
+    };
+
+    match headers.get(ADMIN_TOKEN_HEADER).map(|value| value.to_str()) {
+        None => Err(ApiError::new(StatusCode::UNAUTHORIZED, "missing admin token")),
+        Some(Ok(token)) if token == &**admin_token => Ok(()),
+        Some(_) => Err(ApiError::new(StatusCode::FORBIDDEN, "invalid admin token")),
     }
-    let processing_time = start.elapsed().as_secs_f64() * 1000.0;
-    
-    Json(CpuStressResponse {
-        iterations,
-        result,
-        processing_time_ms: processing_time,
-        timestamp: current_iso_timestamp(),
-    })
 }
 
-pub async fn memory_stress(Path(size_mb): Path<u64>) -> Result<Json<MemoryStressResponse>, StatusCode> {
-    if size_mb > 100 {
-        return Err(StatusCode::BAD_REQUEST);
+// Enabled by ADMIN_TOKEN; takes the same graceful path as SIGTERM so the pool closes cleanly
+#[utoipa::path(
+    post,
+    path = "/admin/shutdown",
+    tag = "general",
+    params(
+        ("x-admin-token" = String, Header, description = "Must match ADMIN_TOKEN"),
+    ),
+    responses(
+        (status = 202, description = "Shutdown started"),
+        (status = 401, description = "Missing x-admin-token"),
+        (status = 403, description = "Wrong x-admin-token"),
+        (status = 404, description = "ADMIN_TOKEN is unset"),
+    ),
+)]
+pub async fn admin_shutdown(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    require_admin(&state, &headers)?;
+
+    state.shutdown.notify_one();
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({
+            "status": "shutting down",
+            "timestamp": current_iso_timestamp()
+        })),
+    ))
+}
+
+// OpenAPI document assembled from the #[utoipa::path] annotations above
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Axum Benchmark API"),
+    paths(
+        read_root,
+        read_item,
+        health_check,
+        readiness_check,
+        version,
+        admin_shutdown,
+        echo_post,
+        echo_batch,
+        echo_get,
+        upload,
+        metrics,
+        stats,
+        route_stats,
+        slowest_requests,
+        sys_memory,
+        sys_cpu,
+        stream_stats,
+        stream_bytes,
+        ws_echo,
+        get_all_items,
+        get_items_page,
+        count_items,
+        item_price_stats,
+        item_price_histogram,
+        random_item,
+        recent_items,
+        recent_items_default,
+        search_items,
+        export_items_csv,
+        get_item,
+        item_exists,
+        create_item,
+        create_items_bulk,
+        update_item,
+        upsert_item_by_name,
+        patch_item,
+        delete_item,
+        delete_all_items,
+        update_items_bulk,
+        delete_items_bulk,
+        batch_get_items,
+        inject_failure,
+        jittered_latency,
+        cpu_stress,
+        concurrency_stress,
+        json_stress,
+        regex_stress,
+        memory_stress,
+        bench_run,
+        disk_stress,
+        db_pragmas,
+        db_pool,
+        db_ping,
+        db_checkpoint,
+        db_analyze,
+        db_explain,
+        db_benchmark_select,
+        db_benchmark_nplus1,
+        start_seed_job,
+        seed_job_progress,
+        db_benchmark_insert,
+        db_benchmark_transactions,
+        db_benchmark_concurrent_writes
+    ),
+    components(schemas(
+        Item,
+        ItemPatch,
+        ItemUpsert,
+        UpsertResponse,
+        ItemResponse,
+        Currency,
+        PaginatedItems,
+        KeysetPage,
+        BulkPriceUpdate,
+        BulkPriceFilter,
+        BulkPriceSet,
+        BulkUpdateResponse,
+        SeedJobStarted,
+        SeedProgress,
+        IdList,
+        EchoRequest,
+        EchoResponse,
+        UploadedField,
+        UploadResponse,
+        HealthResponse,
+        VersionResponse,
+        LatencyDist,
+        LatencyResponse,
+        CpuWorkload,
+        CpuStressResponse,
+        JsonStressResponse,
+        RegexStressRequest,
+        RegexStressResponse,
+        MemoryStressResponse,
+        BenchRunRequest,
+        SelectBenchmarkResult,
+        BenchRunResponse,
+        ItemExistsResponse,
+        ItemCountResponse,
+        PriceStatsResponse,
+        HistogramBucket,
+        PragmaResponse,
+        PoolStatsResponse,
+        DbPingResponse,
+        CheckpointResponse,
+        AnalyzeResponse,
+        QueryPlanRow,
+        DiskStressResponse,
+        StatsResponse,
+        RoutePayloadStats,
+        RouteLatency,
+        SlowRequest,
+        FailResponse,
+        MemoryUsageResponse,
+        CpuUsageResponse
+    )),
+    tags(
+        (name = "general", description = "Hello-world routes"),
+        (name = "observability", description = "Health, readiness, metrics and stats"),
+        (name = "echo", description = "Echo over HTTP and WebSocket"),
+        (name = "items", description = "Item CRUD backed by SQLite"),
+        (name = "database", description = "SQLite introspection and benchmarks"),
+        (name = "stress", description = "CPU, memory, disk and concurrency stress"),
+    )
+)]
+pub struct ApiDoc;
+
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+// Swagger UI pulled from a CDN and pointed at /openapi.json
+pub async fn swagger_ui() -> Html<&'static str> {
+    Html(SWAGGER_UI_HTML)
+}
+
+// Explicit CORS policy for a comma-separated ALLOWED_ORIGINS list
+fn cors_allow_list(origins: &str) -> Result<CorsLayer, String> {
+    let origins = origins
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .map(|origin| {
+            header::HeaderValue::from_str(origin)
+                .map_err(|e| format!("invalid ALLOWED_ORIGINS entry {:?}: {}", origin, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if origins.is_empty() {
+        return Err("ALLOWED_ORIGINS must list at least one origin".to_string());
     }
-    
-    let start = Instant::now();
-    let size_bytes = (size_mb * 1024 * 1024) as usize;
-    let data = vec![0u8; size_bytes];
-    let allocated_bytes = data.len();
-    drop(data);
-    let processing_time = start.elapsed().as_secs_f64() * 1000.0;
-    
-    Ok(Json(MemoryStressResponse {
-        allocated_bytes,
-        allocated_mb: size_mb,
-        processing_time_ms: processing_time,
-        timestamp: current_iso_timestamp(),
-    }))
+
+    Ok(CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers([
+            header::ACCEPT,
+            header::CONTENT_TYPE,
+            header::IF_NONE_MATCH,
+            header::IF_MATCH,
+            header::HeaderName::from_static(API_KEY_HEADER),
+            header::HeaderName::from_static(ADMIN_TOKEN_HEADER),
+            header::HeaderName::from_static(REQUEST_ID_HEADER),
+            header::HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+        ])
+        .expose_headers([
+            header::ETAG,
+            header::RETRY_AFTER,
+            header::HeaderName::from_static("x-process-time"),
+            header::HeaderName::from_static("x-cache"),
+            header::HeaderName::from_static(REQUEST_ID_HEADER),
+        ])
+        .max_age(Duration::from_secs(CORS_MAX_AGE_SECS)))
 }
 
-pub async fn db_benchmark_select(
-    Path(count): Path<u32>,
-    State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    let start = Instant::now();
-    
-    let rows = sqlx::query("SELECT id, name, description, price FROM items LIMIT ?")
-        .bind(count)
-        .fetch_all(&state.db)
+// Parses the PEM files up front so a bad cert or key fails at startup, not on the first handshake
+async fn load_tls_config(cert: &str, key: &str, http2: bool) -> Result<RustlsConfig, String> {
+    // rustls needs a process-wide crypto provider; Err only means one is already installed
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let config = RustlsConfig::from_pem_file(cert, key)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|e| format!("invalid TLS_CERT {:?} / TLS_KEY {:?}: {}", cert, key, e))?;
 
-    let processing_time = start.elapsed().as_secs_f64() * 1000.0;
+    // axum-server advertises h2 via ALPN; keep TLS on HTTP/1.1 unless HTTP2=true
+    if !http2 {
+        let mut server_config = (*config.get_inner()).clone();
+        server_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        config.reload_from_config(Arc::new(server_config));
+    }
+    Ok(config)
+}
 
-    Ok(Json(serde_json::json!({
-        "rows_fetched": rows.len(),
-        "processing_time_ms": processing_time,
-        "timestamp": current_iso_timestamp()
-    })))
+// Cleartext HTTP/2 clients open with this preface (prior knowledge)
+const H2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+// Applied to every accepted socket: waits for a MAX_CONNECTIONS permit, then drops cleartext
+// HTTP/2 unless HTTP2=true (TLS negotiates via ALPN instead). hyper's auto builder ignores
+// http1_only() when serving with upgrades (which WebSockets need), so the preface is checked here.
+#[derive(Debug, Clone)]
+pub struct ConnectionAcceptor {
+    http2: bool,
+    limit: Option<Arc<Semaphore>>,
+}
+
+impl<S: Send + 'static> Accept<TcpStream, S> for ConnectionAcceptor {
+    type Stream = LimitedStream;
+    type Service = S;
+    type Future = BoxFuture<'static, std::io::Result<(LimitedStream, S)>>;
+
+    fn accept(&self, stream: TcpStream, service: S) -> Self::Future {
+        let http2 = self.http2;
+        let limit = self.limit.clone();
+        Box::pin(async move {
+            // Over the limit, connections queue here (already accepted) until one closes
+            let permit = match limit {
+                Some(limit) => Some(limit.acquire_owned().await.map_err(std::io::Error::other)?),
+                None => None,
+            };
+            if !http2 {
+                let mut preface = [0; H2_PREFACE.len()];
+                let read = stream.peek(&mut preface).await?;
+                if read >= 4 && H2_PREFACE.starts_with(&preface[..read]) {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "HTTP/2 is disabled",
+                    ));
+                }
+            }
+            Ok((
+                LimitedStream {
+                    stream,
+                    _permit: permit,
+                },
+                service,
+            ))
+        })
+    }
+}
+
+// A socket that releases its MAX_CONNECTIONS permit when the connection is dropped
+pub struct LimitedStream {
+    stream: TcpStream,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl AsyncRead for LimitedStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for LimitedStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.get_mut().stream).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.get_mut().stream).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.stream.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_shutdown(cx)
+    }
+}
+
+// KEEPALIVE_SECS is how long an idle HTTP/1.1 connection waits for its next request (and the
+// HTTP/2 ping interval); 0 turns HTTP/1.1 keep-alive off
+fn configure_keepalive(builder: &mut auto::Builder<TokioExecutor>, keepalive_secs: u64) {
+    let keepalive = Duration::from_secs(keepalive_secs);
+    builder
+        .http1()
+        .timer(TokioTimer::new())
+        .keep_alive(keepalive_secs > 0);
+    if keepalive_secs > 0 {
+        builder.http1().header_read_timeout(keepalive);
+        builder
+            .http2()
+            .timer(TokioTimer::new())
+            .keep_alive_interval(keepalive);
+    }
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tracing_subscriber::fmt::init();
+// Resolves on Ctrl-C, or SIGTERM on Unix
+async fn shutdown_signal(shutdown: Arc<Notify>) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl-C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+        _ = shutdown.notified() => {},
+    }
+
+    println!("🛑 Shutdown signal received, draining connections...");
+}
+
+// Built by hand instead of #[tokio::main] so WORKER_THREADS can size the runtime
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let default_workers = std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN);
+    let worker_threads: NonZeroUsize = env_parse("WORKER_THREADS", default_workers)?;
+
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(worker_threads.get())
+        .enable_all()
+        .build()?
+        .block_on(run(worker_threads))
+}
+
+async fn run(worker_threads: NonZeroUsize) -> Result<(), Box<dyn std::error::Error>> {
+    // RUST_LOG directives (e.g. axum_benchmark=debug,sqlx=warn), defaulting to info
+    let filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .from_env()
+        .map_err(|e| format!("invalid RUST_LOG: {}", e))?;
+
+    // LOG_FORMAT=json switches to one JSON object per line for log aggregators
+    match std::env::var("LOG_FORMAT").as_deref() {
+        Ok("json") => tracing_subscriber::fmt().json().with_env_filter(filter).init(),
+        Ok("pretty") | Err(_) => tracing_subscriber::fmt().with_env_filter(filter).init(),
+        Ok(other) => {
+            return Err(format!("invalid LOG_FORMAT {:?}: expected json or pretty", other).into())
+        }
+    }
+
+    let db_max_connections: u32 = env_parse("DB_MAX_CONNECTIONS", DEFAULT_DB_MAX_CONNECTIONS)?;
+    let db_min_connections: u32 = env_parse("DB_MIN_CONNECTIONS", 0)?;
+    if db_max_connections == 0 || db_min_connections > db_max_connections {
+        return Err(format!(
+            "DB_MIN_CONNECTIONS ({}) must not exceed DB_MAX_CONNECTIONS ({}), which must be at least 1",
+            db_min_connections, db_max_connections
+        )
+        .into());
+    }
+
+    // Unset keeps the three hand-written sample items
+    let seed_count: Option<u32> = std::env::var("SEED_COUNT")
+        .ok()
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|e| format!("invalid SEED_COUNT {:?}: {}", value, e))
+        })
+        .transpose()?;
+
+    let slow_query_ms: u64 = env_parse("SLOW_QUERY_MS", 0)?;
+    let slow_query_threshold = (slow_query_ms > 0).then(|| Duration::from_millis(slow_query_ms));
+
+    // 0 makes writers fail with SQLITE_BUSY immediately instead of waiting for the lock
+    let db_busy_timeout =
+        Duration::from_millis(env_parse("DB_BUSY_TIMEOUT_MS", DEFAULT_DB_BUSY_TIMEOUT_MS)?);
+
+    let db = init_db(
+        db_max_connections,
+        db_min_connections,
+        seed_count,
+        slow_query_threshold,
+        db_busy_timeout,
+    )
+    .await?;
 
-    let db = init_db().await?;
-    let app_state = AppState { db };
+    let busy_retries: u32 = env_parse("DB_BUSY_RETRIES", DEFAULT_DB_BUSY_RETRIES)?;
+    if busy_retries > MAX_BUSY_RETRIES {
+        return Err(format!("DB_BUSY_RETRIES must not exceed {}", MAX_BUSY_RETRIES).into());
+    }
+
+    let app_state = AppState {
+        db: db.clone(),
+        metrics: Arc::new(Metrics::default()),
+        stats: Arc::new(Mutex::new(StatsState::default())),
+        slow_requests: Arc::new(SlowRequests::new(env_parse(
+            "SLOW_SAMPLE_SIZE",
+            DEFAULT_SLOW_SAMPLE_SIZE,
+        )?)),
+        start_time: Instant::now(),
+        rate_limiter: match env_parse("RATE_LIMIT_RPS", 0u32)? {
+            0 => None,
+            rps => Some(Arc::new(RateLimiter::new(rps))),
+        },
+        api_key: std::env::var("API_KEY").ok().map(Arc::from),
+        // LIST_CACHE_TTL_MS=0 (the default) disables the GET /db/items cache
+        list_cache: match env_parse("LIST_CACHE_TTL_MS", 0)? {
+            0 => None,
+            ttl_ms => Some(Arc::new(ListCache::new(Duration::from_millis(ttl_ms)))),
+        },
+        admin_token: std::env::var("ADMIN_TOKEN").ok().map(Arc::from),
+        shutdown: Arc::new(Notify::new()),
+        seed_jobs: Arc::new(SeedJobs::default()),
+        busy_retries,
+        max_cpu_iterations: env_parse("MAX_CPU_ITERATIONS", DEFAULT_MAX_CPU_ITERATIONS)?,
+        idempotency: Arc::new(IdempotencyStore::new(Duration::from_secs(env_parse(
+            "IDEMPOTENCY_TTL_SECS",
+            DEFAULT_IDEMPOTENCY_TTL_SECS,
+        )?))),
+    };
+
+    let max_body_bytes: usize = env_parse("MAX_BODY_BYTES", DEFAULT_MAX_BODY_BYTES)?;
+    let request_timeout_secs: u64 =
+        env_parse("REQUEST_TIMEOUT_SECS", DEFAULT_REQUEST_TIMEOUT_SECS)?;
+    let global_delay_ms: u64 = env_parse("GLOBAL_DELAY_MS", 0)?;
+
+    let cors = match std::env::var("ALLOWED_ORIGINS") {
+        Ok(origins) => cors_allow_list(&origins)?,
+        Err(_) => CorsLayer::permissive(),
+    };
+
+    let shutdown = app_state.shutdown.clone();
 
     let app = Router::new()
         .route("/", get(read_root))
+        .route("/openapi.json", get(openapi_json))
+        .route("/docs", get(swagger_ui))
         .route("/items/:item_id", get(read_item))
         .route("/health", get(health_check))
+        .route("/ready", get(readiness_check))
+        .route("/version", get(version))
+        .route("/admin/shutdown", post(admin_shutdown))
+        .route("/metrics", get(metrics))
+        .route("/stats", get(stats))
+        .route("/stats/routes", get(route_stats))
+        .route("/stats/slowest", get(slowest_requests))
+        .route("/sys/memory", get(sys_memory))
+        .route("/sys/cpu", get(sys_cpu))
+        .route("/stream/stats", get(stream_stats))
+        .route("/stream/bytes/:size_mb", get(stream_bytes))
         .route("/echo", post(echo_post))
+        .route("/echo/batch", post(echo_batch))
         .route("/echo/:message", get(echo_get))
-        .route("/db/items", get(get_all_items).post(create_item))
-        .route("/db/items/:item_id", get(get_item).put(update_item).delete(delete_item))
+        .route("/upload", post(upload))
+        .route("/ws/echo", get(ws_echo))
+        .route(
+            "/db/items",
+            get(get_all_items).post(create_item).delete(delete_all_items),
+        )
+        .route("/db/items/page", get(get_items_page))
+        .route("/db/items/count", get(count_items))
+        .route("/db/items/stats", get(item_price_stats))
+        .route("/db/items/histogram", get(item_price_histogram))
+        .route("/db/items/random", get(random_item))
+        .route("/db/items/recent", get(recent_items_default))
+        .route("/db/items/recent/:n", get(recent_items))
+        .route("/db/items/search", get(search_items))
+        .route("/db/items/export.csv", get(export_items_csv))
+        .route("/db/items/bulk", post(create_items_bulk).patch(update_items_bulk))
+        .route("/db/items/delete", post(delete_items_bulk))
+        .route("/db/items/batch-get", post(batch_get_items))
+        .route("/db/items/by-name/:name", put(upsert_item_by_name))
+        .route("/db/items/:item_id/exists", get(item_exists))
+        .route("/db/items/:item_id", get(get_item).put(update_item).patch(patch_item).delete(delete_item))
+        .route("/db/pragmas", get(db_pragmas))
+        .route("/db/pool", get(db_pool))
+        .route("/db/ping", get(db_ping))
+        .route("/db/checkpoint", post(db_checkpoint))
+        .route("/db/analyze", post(db_analyze))
+        .route("/db/explain", get(db_explain))
         .route("/db/benchmark/select/:count", get(db_benchmark_select))
+        .route("/db/benchmark/nplus1/:count", get(db_benchmark_nplus1))
+        .route("/db/benchmark/insert/:count", post(db_benchmark_insert))
+        // POST takes a row count and GET a job id; one route because their segments overlap
+        .route("/db/seed/:key", get(seed_job_progress).post(start_seed_job))
+        .route("/db/benchmark/transactions/:count", get(db_benchmark_transactions))
+        .route("/db/benchmark/concurrent-writes/:writers", get(db_benchmark_concurrent_writes))
+        .route("/fail/:rate", get(inject_failure))
+        .route("/latency/:mean_ms", get(jittered_latency))
         .route("/stress/cpu/:iterations", get(cpu_stress))
+        .route("/stress/json/:iterations", post(json_stress))
+        .route("/stress/regex", post(regex_stress))
         .route("/stress/memory/:size_mb", get(memory_stress))
-        .with_state(app_state)
+        .route("/stress/disk/:size_mb", get(disk_stress))
+        .route("/stress/concurrency/:tasks", get(concurrency_stress))
+        .route("/bench/run", post(bench_run))
+        .with_state(app_state.clone())
+        .layer(middleware::from_fn(describe_method_not_allowed))
+        // RequestBodyLimitLayer below is the single source of truth for body size
+        .layer(DefaultBodyLimit::disable())
         .layer(
             ServiceBuilder::new()
-                .layer(CorsLayer::permissive())
-                .layer(middleware::from_fn(add_process_time_header))
+                .layer(cors)
+                .layer(middleware::from_fn(propagate_request_id))
+                .layer(middleware::from_fn(access_log))
+                // Outside the timing middleware so x-process-time excludes compression
+                .layer(CompressionLayer::new())
+                .layer(middleware::from_fn_with_state(app_state.clone(), add_process_time_header))
+                .layer(middleware::from_fn_with_state(app_state.clone(), rate_limit))
+                .layer(middleware::from_fn_with_state(app_state, require_api_key))
+                // Inside the timing middleware so x-process-time includes the injected delay
+                .layer(middleware::from_fn_with_state(
+                    Duration::from_millis(global_delay_ms),
+                    inject_delay,
+                ))
+                .layer(RequestBodyLimitLayer::new(max_body_bytes))
+                .layer(TimeoutLayer::new(Duration::from_secs(request_timeout_secs)))
         );
 
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
-    println!("🚀 Server running on http://0.0.0.0:3000");
-    
-    axum::serve(listener, app).await?;
+    let host: IpAddr = env_parse("HOST", IpAddr::from([0, 0, 0, 0]))?;
+    let port: u16 = env_parse("PORT", 3000)?;
+    // HTTP2=true also accepts HTTP/2 (prior-knowledge h2c in cleartext); HTTP/1.1 only by default
+    let http2: bool = env_parse("HTTP2", false)?;
+
+    // MAX_CONNECTIONS=0 (the default) serves any number of connections at once
+    let max_connections: usize = env_parse("MAX_CONNECTIONS", 0)?;
+    // Unset keeps hyper's defaults
+    let keepalive_secs: Option<u64> = std::env::var("KEEPALIVE_SECS")
+        .ok()
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|e| format!("invalid KEEPALIVE_SECS {:?}: {}", value, e))
+        })
+        .transpose()?;
+
+    // TLS_CERT and TLS_KEY (PEM paths) switch to HTTPS
+    let tls = match (std::env::var("TLS_CERT"), std::env::var("TLS_KEY")) {
+        (Ok(cert), Ok(key)) => Some(load_tls_config(&cert, &key, http2).await?),
+        (Err(_), Err(_)) => None,
+        _ => return Err("TLS_CERT and TLS_KEY must be set together".into()),
+    };
+
+    let listener = std::net::TcpListener::bind(SocketAddr::new(host, port))?;
+    listener.set_nonblocking(true)?;
+    println!(
+        "🚀 Server running on {}://{} ({} worker threads, {})",
+        if tls.is_some() { "https" } else { "http" },
+        listener.local_addr()?,
+        worker_threads,
+        if http2 { "HTTP/1.1 + HTTP/2" } else { "HTTP/1.1" }
+    );
+
+    let handle = axum_server::Handle::new();
+    tokio::spawn({
+        let handle = handle.clone();
+        async move {
+            shutdown_signal(shutdown).await;
+            // Handlers are bounded by the request timeout, so in-flight work has drained by then
+            handle.graceful_shutdown(Some(Duration::from_secs(request_timeout_secs)));
+        }
+    });
+
+    let make_service = app.into_make_service_with_connect_info::<SocketAddr>();
+    let acceptor = ConnectionAcceptor {
+        http2,
+        limit: (max_connections > 0).then(|| Arc::new(Semaphore::new(max_connections))),
+    };
+    match tls {
+        Some(config) => {
+            let mut server = axum_server::from_tcp_rustls(listener, config)
+                .handle(handle)
+                .map(|tls| tls.acceptor(acceptor));
+            if let Some(keepalive_secs) = keepalive_secs {
+                configure_keepalive(server.http_builder(), keepalive_secs);
+            }
+            server.serve(make_service).await?
+        }
+        None => {
+            let mut server = axum_server::from_tcp(listener)
+                .handle(handle)
+                .acceptor(acceptor);
+            if let Some(keepalive_secs) = keepalive_secs {
+                configure_keepalive(server.http_builder(), keepalive_secs);
+            }
+            server.serve(make_service).await?
+        }
+    }
+
+    // Fold the WAL back into the main database before closing the pool
+    if let Err(e) = sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(&db).await {
+        eprintln!("WAL checkpoint on shutdown failed: {:?}", e);
+    }
+    db.close().await;
+    println!("✅ Shutdown complete");
+
     Ok(())
 }